@@ -0,0 +1,324 @@
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+use super::grid::Grid;
+use super::path::{GridPath, PATH_MARK, VISITED_MARK};
+use crate::trace::SearchObserver;
+
+// =============================================================================
+// 🎬 探索のアニメーション（ANSIエスケープで1手ずつ描き直す）
+// =============================================================================
+//
+// TextTrace の「現在地: (1, 2)」が並ぶ表示では、探索がどう広がるかが見えにくいので、
+// 1手ごとに地図全体を描き直して色で塗り分けます。
+//
+//   黄色 = フロンティア（キュー/スタックに入っていて、まだ取り出していない）
+//   青   = 訪問済み（取り出して隣を調べ終わった）
+//   赤   = いま取り出したマス
+//   緑   = 最後に見つかった経路
+//
+// SearchRecording は SearchObserver なので、BFSにもDFSにもそのまま渡せます。
+// 記録するのは「取り出したマス」と「そのとき見つけたマス」だけで、
+// 各コマの地図は再生するときに組み立て直します（大きな迷路でもメモリを食わない）。
+//
+// 色なしの文字でも描けます（'+' = フロンティア, 'o' = 訪問済み, '@' = 現在地, '*' = 経路）。
+// dump_frames はこの文字版を1コマ1ファイルで書き出すので、テストや差分の確認に使えます。
+
+// 1コマでのマスの状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellState {
+    Unseen,
+    Frontier,
+    Visited,
+    Current,
+    Path,
+}
+
+pub const FRONTIER_MARK: char = '+';
+pub const CURRENT_MARK: char = '@';
+
+// 探索の1手: 取り出したマスと、そのとき新しく見つけたマス
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Step {
+    current: (usize, usize),
+    discovered: Vec<(usize, usize)>,
+}
+
+// 探索の記録（observerとして探索関数に渡す）
+#[derive(Debug, Clone)]
+pub struct SearchRecording {
+    maze: Grid<char>,
+    // スタート地点（最初のコマでフロンティアとして描くマス）
+    // BFSは取り出す前に訪問済みにするので on_visit で、
+    // DFSは先に取り出すので最初の on_pop で記録する
+    sources: Vec<(usize, usize)>,
+    steps: Vec<Step>,
+    path: Vec<(usize, usize)>,
+}
+
+impl SearchRecording {
+    pub fn new(maze: &Grid<char>) -> SearchRecording {
+        SearchRecording {
+            maze: maze.clone(),
+            sources: Vec::new(),
+            steps: Vec::new(),
+            path: Vec::new(),
+        }
+    }
+
+    // 探索が見つけた経路を最後のコマに描く
+    pub fn set_path(&mut self, path: &GridPath) {
+        self.path = path.cells.clone();
+    }
+
+    // コマの数（最初の状態 + 1手ごと + 経路）
+    pub fn frame_count(&self) -> usize {
+        self.steps.len() + 2
+    }
+
+    // 全てのコマを順番に f に渡す（f がエラーを返したらそこで止める）
+    pub fn for_each_frame<F>(&self, mut f: F) -> Result<(), String>
+    where
+        F: FnMut(usize, &Grid<CellState>) -> Result<(), String>,
+    {
+        let mut state = Grid::new(self.maze.rows(), self.maze.cols(), CellState::Unseen);
+        for &source in &self.sources {
+            state[source] = CellState::Frontier;
+        }
+        f(0, &state)?;
+
+        let mut previous: Option<(usize, usize)> = None;
+        for (i, step) in self.steps.iter().enumerate() {
+            if let Some(cell) = previous {
+                state[cell] = CellState::Visited;
+            }
+            state[step.current] = CellState::Current;
+            for &cell in &step.discovered {
+                state[cell] = CellState::Frontier;
+            }
+            previous = Some(step.current);
+            f(i + 1, &state)?;
+        }
+
+        if let Some(cell) = previous {
+            state[cell] = CellState::Visited;
+        }
+        for &cell in &self.path {
+            state[cell] = CellState::Path;
+        }
+        f(self.steps.len() + 1, &state)
+    }
+
+    // 1コマを文字列にする（color が true ならANSIの背景色で塗る）
+    // スタート・ゴールなど '.' と '#' 以外の文字は、色だけ付けて文字はそのまま
+    pub fn render(&self, state: &Grid<CellState>, color: bool) -> String {
+        let mut out = String::new();
+        for row in 0..self.maze.rows() {
+            for col in 0..self.maze.cols() {
+                let original = self.maze[(row, col)];
+                let cell = state[(row, col)];
+                if color {
+                    out.push_str(&paint(original, cell));
+                } else {
+                    out.push(mark(original, cell));
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+impl SearchObserver<(usize, usize)> for SearchRecording {
+    fn on_pop(&mut self, node: (usize, usize), _cost: u64) {
+        if self.steps.is_empty() && self.sources.is_empty() {
+            self.sources.push(node);
+        }
+        self.steps.push(Step {
+            current: node,
+            discovered: Vec::new(),
+        });
+    }
+
+    // 訪問済みのマスを取り出して飛ばしただけなら、その手はコマにしない
+    fn on_skip_stale(&mut self, node: (usize, usize), _cost: u64) {
+        if self
+            .steps
+            .last()
+            .is_some_and(|step| step.current == node && step.discovered.is_empty())
+        {
+            self.steps.pop();
+        }
+    }
+
+    fn on_visit(&mut self, node: (usize, usize), _cost: u64) {
+        if self.steps.is_empty() {
+            self.sources.push(node);
+        }
+    }
+
+    fn on_relax(&mut self, _from: (usize, usize), to: (usize, usize), _cost: u64) {
+        if let Some(step) = self.steps.last_mut() {
+            step.discovered.push(to);
+        }
+    }
+}
+
+// 色なしで表示する文字
+fn mark(original: char, cell: CellState) -> char {
+    if original != '.' {
+        return original;
+    }
+    match cell {
+        CellState::Unseen => '.',
+        CellState::Frontier => FRONTIER_MARK,
+        CellState::Visited => VISITED_MARK,
+        CellState::Current => CURRENT_MARK,
+        CellState::Path => PATH_MARK,
+    }
+}
+
+// ANSIの背景色を付けた1マス（壁は灰色の文字のまま）
+fn paint(original: char, cell: CellState) -> String {
+    if original == '#' {
+        return "\x1b[90m#\x1b[0m".to_string();
+    }
+    let background = match cell {
+        CellState::Unseen => return original.to_string(),
+        CellState::Frontier => 43,
+        CellState::Visited => 44,
+        CellState::Current => 41,
+        CellState::Path => 42,
+    };
+    format!("\x1b[{}m{}\x1b[0m", background, original)
+}
+
+// ターミナルでアニメーションを再生する（1コマごとに delay だけ待つ）
+// 画面を消してから、毎回カーソルを左上に戻して描き直す
+pub fn play<W: Write>(
+    recording: &SearchRecording,
+    out: &mut W,
+    delay: Duration,
+) -> Result<(), String> {
+    let total = recording.frame_count();
+    write!(out, "\x1b[2J").map_err(|e| format!("書き込めません: {}", e))?;
+    recording.for_each_frame(|i, state| {
+        write!(out, "\x1b[H{}", recording.render(state, true))
+            .and_then(|_| writeln!(out, "コマ {}/{}\x1b[K", i + 1, total))
+            .and_then(|_| out.flush())
+            .map_err(|e| format!("書き込めません: {}", e))?;
+        thread::sleep(delay);
+        Ok(())
+    })
+}
+
+// 色なしのコマを dir/frame_0000.txt, frame_0001.txt, ... に書き出す
+// 書き出したファイルの数を返す
+pub fn dump_frames<P: AsRef<Path>>(recording: &SearchRecording, dir: P) -> Result<usize, String> {
+    let dir = dir.as_ref();
+    fs::create_dir_all(dir)
+        .map_err(|e| format!("フォルダを作れません({}): {}", dir.display(), e))?;
+    recording.for_each_frame(|i, state| {
+        let file = dir.join(format!("frame_{:04}.txt", i));
+        fs::write(&file, recording.render(state, false))
+            .map_err(|e| format!("ファイルを書き込めません({}): {}", file.display(), e))
+    })?;
+    Ok(recording.frame_count())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dfs_bfs_practice::maze::{bfs_shortest_path, dfs_find_path};
+
+    fn frames(recording: &SearchRecording) -> Vec<String> {
+        let mut frames = Vec::new();
+        recording
+            .for_each_frame(|_, state| {
+                frames.push(recording.render(state, false));
+                Ok(())
+            })
+            .unwrap();
+        frames
+    }
+
+    #[test]
+    fn test_bfs_frames() {
+        let maze = Grid::parse("S..\n.#.\n..G").unwrap();
+        let mut recording = SearchRecording::new(&maze);
+        let solution = bfs_shortest_path(&maze, (0, 0), (2, 2), &mut recording);
+        recording.set_path(solution.path.as_ref().unwrap());
+
+        let frames = frames(&recording);
+        assert_eq!(frames.len(), recording.frame_count());
+        assert_eq!(frames[0], "S..\n.#.\n..G\n");
+        // 1手目: スタートを取り出して、右と下がフロンティアになる
+        assert_eq!(frames[1], "S+.\n+#.\n..G\n");
+        // 2手目: 下のマスが現在地になり、その下がフロンティアに加わる
+        assert_eq!(frames[2], "S+.\n@#.\n+.G\n");
+        // 3手目: 取り出し終わったマスは訪問済み
+        assert_eq!(frames[3], "S@+\no#.\n+.G\n");
+        // 最後: 経路（スタートとゴールは元の文字のまま）
+        let last = frames.last().unwrap();
+        assert_eq!(last.matches(PATH_MARK).count(), 3);
+        assert!(!last.contains(CURRENT_MARK) && !last.contains(FRONTIER_MARK));
+    }
+
+    #[test]
+    fn test_dfs_frames_and_colors() {
+        let maze = Grid::parse("S...\n.##.\n...G").unwrap();
+        let mut recording = SearchRecording::new(&maze);
+        let solution = dfs_find_path(&maze, (0, 0), (2, 3), &mut recording);
+        recording.set_path(solution.path.as_ref().unwrap());
+        let frames = frames(&recording);
+        // どのコマでも現在地は高々1マス
+        assert!(frames
+            .iter()
+            .all(|frame| frame.matches(CURRENT_MARK).count() <= 1));
+
+        let mut state = Grid::new(1, 2, CellState::Unseen);
+        state[(0, 1)] = CellState::Frontier;
+        let one = SearchRecording::new(&Grid::parse("#.").unwrap());
+        assert_eq!(
+            one.render(&state, true),
+            "\x1b[90m#\x1b[0m\x1b[43m.\x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn test_dfs_first_frame_shows_start() {
+        // DFSは on_visit より先に on_pop が来るが、最初のコマにスタートが出る
+        let maze = Grid::parse("...\n.#.\n..G").unwrap();
+        let mut recording = SearchRecording::new(&maze);
+        dfs_find_path(&maze, (0, 0), (2, 2), &mut recording);
+
+        let frames = frames(&recording);
+        assert_eq!(frames[0], "+..\n.#.\n..G\n");
+        // 1手目: スタートを取り出して、下と右がフロンティアになる
+        assert_eq!(frames[1], "@+.\n+#.\n..G\n");
+    }
+
+    #[test]
+    fn test_dump_frames() {
+        let maze = Grid::parse("S.G").unwrap();
+        let mut recording = SearchRecording::new(&maze);
+        let solution = bfs_shortest_path(&maze, (0, 0), (0, 2), &mut recording);
+        recording.set_path(solution.path.as_ref().unwrap());
+
+        let dir = std::env::temp_dir().join(format!("rust_learning_frames_{}", std::process::id()));
+        assert_eq!(dump_frames(&recording, &dir), Ok(5));
+        assert_eq!(
+            fs::read_to_string(dir.join("frame_0001.txt")).unwrap(),
+            "S+G\n"
+        );
+        assert_eq!(
+            fs::read_to_string(dir.join("frame_0004.txt")).unwrap(),
+            "S*G\n"
+        );
+        assert!(!dir.join("frame_0005.txt").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,543 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use super::grid::Grid;
+use super::labeling::Connectivity;
+use super::path::{trace_path, GridPath};
+use crate::trace::SearchObserver;
+
+// =============================================================================
+// ⭐ A* とジャンプポイントサーチ（JPS）
+// =============================================================================
+//
+// BFSはゴールの方向を気にせず、スタートから同心円状に広がります。
+// 広い地図ではゴールに着くまでにほぼ全てのマスを調べてしまいます。
+//
+// A*は「ここまでのコスト g」に「ゴールまでの見積もり h」を足した
+//   f = g + h
+// が小さい順に調べます。hが実際の残りコストを超えなければ（許容的なら）、
+// ダイクストラ法と同じ最短経路が、もっと少ない展開数で求まります。
+//
+// コストは整数で扱うため、まっすぐ1マスを STRAIGHT_COST、
+// 斜め1マスを DIAGONAL_COST（√2 × 1000 より少し大きい 1415）とします。
+//
+// 見積もり（ヒューリスティック）:
+//   マンハッタン距離 … |dr| + |dc|           4近傍で許容的（8近傍では大きすぎる）
+//   オクタイル距離   … 斜めに進めるだけ進む   8近傍でぴったり
+//   ユークリッド距離 … 直線距離               どちらでも許容的（少し小さめ）
+//
+// 8近傍でマンハッタン距離を指定すると最短でない経路を返すことがあるので、
+// astar はその組み合わせをエラーにします（見積もりは呼び出し側が選び直す）。
+//
+// 8近傍の斜め移動は、角をかすめないように
+// 「縦と横の両方のマスが通れるときだけ」許可します。
+
+pub const STRAIGHT_COST: u64 = 1000;
+pub const DIAGONAL_COST: u64 = 1415;
+
+// ゴールまでの見積もり
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Heuristic {
+    Manhattan,
+    Octile,
+    Euclidean,
+}
+
+impl Heuristic {
+    pub fn estimate(self, from: (usize, usize), to: (usize, usize)) -> u64 {
+        let dr = from.0.abs_diff(to.0) as u64;
+        let dc = from.1.abs_diff(to.1) as u64;
+        match self {
+            Heuristic::Manhattan => STRAIGHT_COST * (dr + dc),
+            Heuristic::Octile => {
+                let (long, short) = (dr.max(dc), dr.min(dc));
+                STRAIGHT_COST * long + (DIAGONAL_COST - STRAIGHT_COST) * short
+            }
+            Heuristic::Euclidean => {
+                (STRAIGHT_COST as f64 * ((dr * dr + dc * dc) as f64).sqrt()).floor() as u64
+            }
+        }
+    }
+
+    // connectivity で許容的か（実際の残りコストを超えないか）
+    // 8近傍のマンハッタン距離は斜め移動のぶん大きすぎるので false
+    pub fn is_admissible_for(self, connectivity: Connectivity) -> bool {
+        !matches!(
+            (self, connectivity),
+            (Heuristic::Manhattan, Connectivity::Eight)
+        )
+    }
+}
+
+// 探索の結果: 経路と、展開した（取り出して調べた）マスの数
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchReport {
+    pub path: Option<GridPath>,
+    pub expanded: usize,
+}
+
+// 1手の移動コスト
+fn step_cost((dr, dc): (isize, isize)) -> u64 {
+    if dr != 0 && dc != 0 {
+        DIAGONAL_COST
+    } else {
+        STRAIGHT_COST
+    }
+}
+
+// posから移動できる隣のマスと、その移動のコスト
+fn moves<'a, T, F>(
+    grid: &'a Grid<T>,
+    pos: (usize, usize),
+    connectivity: Connectivity,
+    passable: &'a F,
+) -> impl Iterator<Item = ((usize, usize), u64)> + 'a
+where
+    F: Fn(&T) -> bool,
+{
+    let walkable = move |r: isize, c: isize| {
+        grid.checked_position(r, c)
+            .is_some_and(|p| passable(&grid[p]))
+    };
+    let (row, col) = (pos.0 as isize, pos.1 as isize);
+    connectivity
+        .directions()
+        .iter()
+        .filter_map(move |&(dr, dc)| {
+            if !walkable(row + dr, col + dc) {
+                return None;
+            }
+            // 斜めは縦と横の両方が通れるときだけ
+            if dr != 0 && dc != 0 && !(walkable(row + dr, col) && walkable(row, col + dc)) {
+                return None;
+            }
+            let next = ((row + dr) as usize, (col + dc) as usize);
+            Some((next, step_cost((dr, dc))))
+        })
+}
+
+// グリッド上のA*
+// passable(マスの値) が true のマスだけを通る
+// heuristic が connectivity で許容的でなければエラー（8近傍のマンハッタン距離）
+pub fn astar<T, F, O>(
+    grid: &Grid<T>,
+    start: (usize, usize),
+    goal: (usize, usize),
+    connectivity: Connectivity,
+    heuristic: Heuristic,
+    passable: F,
+    observer: &mut O,
+) -> Result<SearchReport, String>
+where
+    F: Fn(&T) -> bool,
+    O: SearchObserver<(usize, usize)>,
+{
+    if !heuristic.is_admissible_for(connectivity) {
+        return Err(format!(
+            "{:?} は {:?} では許容的でないので使えません（Octile か Euclidean を指定してください）",
+            heuristic, connectivity
+        ));
+    }
+    let mut g: Grid<u64> = Grid::new(grid.rows(), grid.cols(), u64::MAX);
+    let mut parent: Grid<Option<(usize, usize)>> = Grid::new(grid.rows(), grid.cols(), None);
+    let mut closed = Grid::new(grid.rows(), grid.cols(), false);
+    let mut heap = BinaryHeap::new();
+    let mut expanded = 0;
+
+    g[start] = 0;
+    // (f, h, マス): fが同じならゴールに近い（hが小さい）方を先に調べる
+    let h = heuristic.estimate(start, goal);
+    heap.push(Reverse((h, h, start)));
+
+    while let Some(Reverse((f, _, current))) = heap.pop() {
+        observer.on_pop(current, f);
+        if closed[current] {
+            observer.on_skip_stale(current, f);
+            continue;
+        }
+        closed[current] = true;
+        expanded += 1;
+        observer.on_visit(current, g[current]);
+
+        if current == goal {
+            let path = GridPath {
+                cost: g[goal],
+                cells: trace_path(&parent, goal),
+            };
+            return Ok(SearchReport {
+                path: Some(path),
+                expanded,
+            });
+        }
+
+        for (next, cost) in moves(grid, current, connectivity, &passable) {
+            let new_g = g[current] + cost;
+            if new_g < g[next] {
+                g[next] = new_g;
+                parent[next] = Some(current);
+                observer.on_relax(current, next, new_g);
+                let h = heuristic.estimate(next, goal);
+                heap.push(Reverse((new_g + h, h, next)));
+            }
+        }
+    }
+
+    Ok(SearchReport {
+        path: None,
+        expanded,
+    })
+}
+
+// =============================================================================
+// 🦘 ジャンプポイントサーチ（8近傍・全てのマスのコストが同じ場合）
+// =============================================================================
+//
+// 何もない広場では、A*でも同じコストの経路がたくさんあるため
+// 多くのマスを展開してしまいます。
+//
+// JPSは「まっすぐ（または斜めに）進み続けても損をしない間は進み続ける」ことで、
+// 途中のマスをヒープに入れずに飛ばします。止まるのは
+//   - ゴールに着いたとき
+//   - 壁の角を曲がる必要が出てくるマス（強制隣接があるマス）
+//   - 斜めに進む途中で、縦か横にジャンプポイントが見つかったマス
+// だけです。止まったマス（ジャンプポイント）だけをA*で展開します。
+//
+// 角をかすめない斜め移動のルール（A*と同じ）に合わせた枝刈りを使います。
+
+struct Jumper<'a, T, F> {
+    grid: &'a Grid<T>,
+    passable: &'a F,
+    goal: (isize, isize),
+}
+
+impl<T, F: Fn(&T) -> bool> Jumper<'_, T, F> {
+    fn walkable(&self, r: isize, c: isize) -> bool {
+        self.grid
+            .checked_position(r, c)
+            .is_some_and(|p| (self.passable)(&self.grid[p]))
+    }
+
+    // (r, c) から (dr, dc) の向きに進み、次のジャンプポイントを探す
+    // (r, c) は1歩進んだ後のマス
+    fn jump(&self, mut r: isize, mut c: isize, dr: isize, dc: isize) -> Option<(isize, isize)> {
+        loop {
+            if !self.walkable(r, c) {
+                return None;
+            }
+            if (r, c) == self.goal {
+                return Some((r, c));
+            }
+
+            if dr != 0 && dc != 0 {
+                // 斜め: 縦か横にジャンプポイントがあれば、ここで止まる
+                if self.jump(r + dr, c, dr, 0).is_some() || self.jump(r, c + dc, 0, dc).is_some() {
+                    return Some((r, c));
+                }
+                // 角をかすめる斜め移動はできない
+                if !(self.walkable(r + dr, c) && self.walkable(r, c + dc)) {
+                    return None;
+                }
+            } else if dr == 0 {
+                // 横: 後ろの上下が壁で、今のマスの上下が空いていれば曲がる必要がある
+                if (self.walkable(r - 1, c) && !self.walkable(r - 1, c - dc))
+                    || (self.walkable(r + 1, c) && !self.walkable(r + 1, c - dc))
+                {
+                    return Some((r, c));
+                }
+            } else if (self.walkable(r, c - 1) && !self.walkable(r - dr, c - 1))
+                || (self.walkable(r, c + 1) && !self.walkable(r - dr, c + 1))
+            {
+                // 縦: 横と同じ考え方
+                return Some((r, c));
+            }
+
+            r += dr;
+            c += dc;
+        }
+    }
+
+    // 親から来た向きをもとに、調べる必要のある方向だけを残す（枝刈り）
+    fn directions(
+        &self,
+        (r, c): (isize, isize),
+        from: Option<(isize, isize)>,
+    ) -> Vec<(isize, isize)> {
+        let (dr, dc) = match from {
+            Some((pr, pc)) => ((r - pr).signum(), (c - pc).signum()),
+            None => {
+                // スタート地点は全方向
+                return Connectivity::Eight
+                    .directions()
+                    .iter()
+                    .copied()
+                    .filter(|&(dr, dc)| {
+                        self.walkable(r + dr, c + dc)
+                            && (dr == 0
+                                || dc == 0
+                                || (self.walkable(r + dr, c) && self.walkable(r, c + dc)))
+                    })
+                    .collect();
+            }
+        };
+
+        let mut result = Vec::new();
+        if dr != 0 && dc != 0 {
+            let vertical = self.walkable(r + dr, c);
+            let horizontal = self.walkable(r, c + dc);
+            if vertical {
+                result.push((dr, 0));
+            }
+            if horizontal {
+                result.push((0, dc));
+            }
+            if vertical && horizontal {
+                result.push((dr, dc));
+            }
+        } else if dr == 0 {
+            let next = self.walkable(r, c + dc);
+            let up = self.walkable(r - 1, c);
+            let down = self.walkable(r + 1, c);
+            if next {
+                result.push((0, dc));
+                if up {
+                    result.push((-1, dc));
+                }
+                if down {
+                    result.push((1, dc));
+                }
+            }
+            if up {
+                result.push((-1, 0));
+            }
+            if down {
+                result.push((1, 0));
+            }
+        } else {
+            let next = self.walkable(r + dr, c);
+            let left = self.walkable(r, c - 1);
+            let right = self.walkable(r, c + 1);
+            if next {
+                result.push((dr, 0));
+                if left {
+                    result.push((dr, -1));
+                }
+                if right {
+                    result.push((dr, 1));
+                }
+            }
+            if left {
+                result.push((0, -1));
+            }
+            if right {
+                result.push((0, 1));
+            }
+        }
+        result
+    }
+}
+
+// ジャンプポイントサーチ（8近傍、全てのマスのコストが同じ）
+// expanded は展開したジャンプポイントの数
+pub fn jump_point_search<T, F>(
+    grid: &Grid<T>,
+    start: (usize, usize),
+    goal: (usize, usize),
+    passable: F,
+) -> SearchReport
+where
+    F: Fn(&T) -> bool,
+{
+    let jumper = Jumper {
+        grid,
+        passable: &passable,
+        goal: (goal.0 as isize, goal.1 as isize),
+    };
+    let mut g: Grid<u64> = Grid::new(grid.rows(), grid.cols(), u64::MAX);
+    let mut parent: Grid<Option<(usize, usize)>> = Grid::new(grid.rows(), grid.cols(), None);
+    let mut closed = Grid::new(grid.rows(), grid.cols(), false);
+    let mut heap = BinaryHeap::new();
+    let mut expanded = 0;
+
+    g[start] = 0;
+    let h = Heuristic::Octile.estimate(start, goal);
+    heap.push(Reverse((h, h, start)));
+
+    while let Some(Reverse((_, _, current))) = heap.pop() {
+        if closed[current] {
+            continue;
+        }
+        closed[current] = true;
+        expanded += 1;
+
+        if current == goal {
+            let path = GridPath {
+                cost: g[goal],
+                cells: fill_jumps(&trace_path(&parent, goal)),
+            };
+            return SearchReport {
+                path: Some(path),
+                expanded,
+            };
+        }
+
+        let here = (current.0 as isize, current.1 as isize);
+        let from = parent[current].map(|(r, c)| (r as isize, c as isize));
+        for (dr, dc) in jumper.directions(here, from) {
+            let (jr, jc) = match jumper.jump(here.0 + dr, here.1 + dc, dr, dc) {
+                Some(point) => point,
+                None => continue,
+            };
+            let next = (jr as usize, jc as usize);
+            // ジャンプポイントまではまっすぐか斜め一直線なので、オクタイル距離がそのままコスト
+            let new_g = g[current] + Heuristic::Octile.estimate(current, next);
+            if new_g < g[next] {
+                g[next] = new_g;
+                parent[next] = Some(current);
+                let h = Heuristic::Octile.estimate(next, goal);
+                heap.push(Reverse((new_g + h, h, next)));
+            }
+        }
+    }
+
+    SearchReport {
+        path: None,
+        expanded,
+    }
+}
+
+// ジャンプポイントの列を、1マスずつの経路に展開する
+fn fill_jumps(points: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let mut cells = vec![points[0]];
+    for pair in points.windows(2) {
+        let (mut r, mut c) = (pair[0].0 as isize, pair[0].1 as isize);
+        let (tr, tc) = (pair[1].0 as isize, pair[1].1 as isize);
+        let (dr, dc) = ((tr - r).signum(), (tc - c).signum());
+        while (r, c) != (tr, tc) {
+            r += dr;
+            c += dc;
+            cells.push((r as usize, c as usize));
+        }
+    }
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dfs_bfs_practice::maze::bfs_shortest_distance;
+    use crate::rng::XorShift64;
+    use crate::trace::NoTrace;
+
+    fn random_maze(rng: &mut XorShift64, rows: usize, cols: usize) -> Grid<char> {
+        let mut grid = Grid::from_fn(rows, cols, |_, _| if rng.chance(0.3) { '#' } else { '.' });
+        grid[(0, 0)] = 'S';
+        grid[(rows - 1, cols - 1)] = 'G';
+        grid
+    }
+
+    // 経路が連続していて、壁を通らず、角をかすめていないか
+    fn assert_valid(grid: &Grid<char>, path: &GridPath) {
+        let mut cost = 0;
+        for pair in path.cells.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            assert!(a.0.abs_diff(b.0) <= 1 && a.1.abs_diff(b.1) <= 1 && a != b);
+            assert_ne!(grid[b], '#');
+            if a.0 != b.0 && a.1 != b.1 {
+                assert_ne!(grid[(a.0, b.1)], '#');
+                assert_ne!(grid[(b.0, a.1)], '#');
+                cost += DIAGONAL_COST;
+            } else {
+                cost += STRAIGHT_COST;
+            }
+        }
+        assert_eq!(cost, path.cost);
+    }
+
+    #[test]
+    fn test_four_connected_matches_bfs() {
+        let mut rng = XorShift64::new(38);
+        for _ in 0..30 {
+            let grid = random_maze(&mut rng, 12, 15);
+            let goal = (11, 14);
+            let bfs = bfs_shortest_distance(&grid, (0, 0), goal, &mut NoTrace);
+            for heuristic in [
+                Heuristic::Manhattan,
+                Heuristic::Octile,
+                Heuristic::Euclidean,
+            ] {
+                let report = astar(
+                    &grid,
+                    (0, 0),
+                    goal,
+                    Connectivity::Four,
+                    heuristic,
+                    |&c| c != '#',
+                    &mut NoTrace,
+                )
+                .unwrap();
+                let cost = report.path.as_ref().map(|p| p.cost / STRAIGHT_COST);
+                assert_eq!(cost, bfs.map(|d| d as u64), "{:?}", heuristic);
+            }
+        }
+    }
+
+    #[test]
+    fn test_jps_matches_astar_on_eight_connected() {
+        let mut rng = XorShift64::new(380);
+        let mut found = 0;
+        for _ in 0..50 {
+            let grid = random_maze(&mut rng, 14, 18);
+            let goal = (13, 17);
+            let search = |heuristic| {
+                astar(
+                    &grid,
+                    (0, 0),
+                    goal,
+                    Connectivity::Eight,
+                    heuristic,
+                    |&c| c != '#',
+                    &mut NoTrace,
+                )
+            };
+            let octile = search(Heuristic::Octile).unwrap();
+            let euclid = search(Heuristic::Euclidean).unwrap();
+            // 8近傍のマンハッタン距離は許容的でないのでエラー
+            assert!(search(Heuristic::Manhattan).is_err());
+            let jps = jump_point_search(&grid, (0, 0), goal, |&c| c != '#');
+            let cost = |report: &SearchReport| report.path.as_ref().map(|p| p.cost);
+            assert_eq!(cost(&octile), cost(&euclid));
+            assert_eq!(cost(&octile), cost(&jps), "\n{}", grid);
+            if let Some(path) = &jps.path {
+                assert_valid(&grid, path);
+                found += 1;
+            }
+        }
+        assert!(found > 10);
+    }
+
+    #[test]
+    fn test_open_map_expands_fewer_nodes() {
+        let mut grid = Grid::new(60, 60, '.');
+        for row in 10..50 {
+            grid[(row, 30)] = '#';
+        }
+        let (start, goal) = ((30, 5), (30, 55));
+        let octile = astar(
+            &grid,
+            start,
+            goal,
+            Connectivity::Eight,
+            Heuristic::Octile,
+            |&c| c != '#',
+            &mut NoTrace,
+        )
+        .unwrap();
+        let jps = jump_point_search(&grid, start, goal, |&c| c != '#');
+        assert_eq!(
+            octile.path.as_ref().unwrap().cost,
+            jps.path.as_ref().unwrap().cost
+        );
+        assert!(jps.expanded < octile.expanded);
+        assert!(octile.expanded < 60 * 60);
+    }
+}
@@ -1,7 +1,14 @@
-use std::collections::{VecDeque, HashMap};
+use std::collections::{HashMap, VecDeque};
+
+use rust_learning::dfs_bfs_practice::distance_field::{euclidean_distance, nearest_target_field};
+use rust_learning::dfs_bfs_practice::grid::Grid;
 
 // TODO: BFSを使って、スタートから各マスへの最短距離を計算する関数
-fn bfs_distance_map(grid: &Vec<Vec<char>>, start: (isize, isize), target_len: usize) -> HashMap<(usize, usize), usize> {
+fn bfs_distance_map(
+    grid: &Grid<char>,
+    start: (usize, usize),
+    target_len: usize,
+) -> HashMap<(usize, usize), usize> {
     // ヒント:
     // 1. キューとvisitedを準備
     // 2. スタート地点をキューに入れる
@@ -9,61 +16,45 @@ fn bfs_distance_map(grid: &Vec<Vec<char>>, start: (isize, isize), target_len: us
     // 4. 壁('#')は通れない、'.'と'S'と'T'は通れる
     // 5. 結果をHashMapで返す（座標 → 距離）
     let mut queue = VecDeque::new();
-    let mut visited: HashMap<(isize, isize), bool> = HashMap::new();
-    let directions:[(isize, isize);4] = [(0,1),(1,0),(0,-1),(-1,0)];
+    let mut visited: HashMap<(usize, usize), bool> = HashMap::new();
     let mut result = HashMap::new();
-    queue.push_back((start,0));
+    queue.push_back((start, 0));
     visited.insert(start, true);
-    while !queue.is_empty() {
-        let (front, dis) = queue.pop_front().unwrap();
+    while let Some((front, dis)) = queue.pop_front() {
         if result.len() == target_len {
             break;
         };
-        for direction in directions {
-            // 修正: 不要な型キャストを削除（frontは既に(isize, isize)型）
-            // let (row, col) = front as (isize, isize);  // 修正前: 不要な型キャスト
-            let (row, col) = front;  // 修正後: frontは既に(isize, isize)型なので直接使用
-            let (next_r, next_c) = direction;
-            let target_row = row + next_r;
-            let target_col = col + next_c;
-
-            if visited.contains_key(&(target_row, target_col)) {
+        // 修正: isizeへのキャストと範囲チェックをやめて、Grid::neighbors4を使う
+        for target in grid.neighbors4(front) {
+            if visited.contains_key(&target) {
                 continue;
             }
 
-            if target_row < 0 || target_col < 0 {
-                continue;
-            };
-
-            if (grid.len() - 1) < target_row as usize || (grid[target_row as usize ].len() - 1) < target_col as usize {
+            if grid[target] == '#' {
                 continue;
             }
 
-            if grid[target_row as usize][target_col as usize] == '#' {
-                continue;
-            } 
-
-
-            if grid[target_row as usize][target_col as usize] == 'T' {
-                result.insert(((target_row  as usize, target_col as usize)), dis + 1);
+            if grid[target] == 'T' {
+                result.insert(target, dis + 1);
             }
 
-            visited.insert((target_row, target_col), true);
-            queue.push_back(((target_row, target_col), dis + 1));
-
-        };
-    };
-    return result;
-    
+            visited.insert(target, true);
+            queue.push_back((target, dis + 1));
+        }
+    }
+    result
 }
 
 // TODO: 複数のターゲットの中で最も近いものを見つける関数
-fn find_nearest_target(distances: &HashMap<(usize, usize), usize>, targets: &Vec<(usize, usize)>) -> Option<((usize, usize), usize)> {
+fn find_nearest_target(
+    distances: &HashMap<(usize, usize), usize>,
+    targets: &[(usize, usize)],
+) -> Option<((usize, usize), usize)> {
     // ヒント:
     // 1. 各ターゲットについて、distancesから距離を取得
     // 2. 到達可能なターゲットの中で最小距離のものを返す
     // 3. どのターゲットにも到達できない場合はNone
-    
+
     // ========== 修正前のコード ==========
     // let mut a: &usize = &usize::MAX;
     // let mut current_target: (usize, usize) = (0,0);
@@ -80,12 +71,12 @@ fn find_nearest_target(distances: &HashMap<(usize, usize), usize>, targets: &Vec
     // }
     // None
     // =====================================
-    
+
     // ========== 修正後のコード ==========
     // 修正点1: より安全で明確な実装に変更
     let mut min_distance = usize::MAX;
     let mut nearest_target = None;
-    
+
     for target in targets {
         // 修正点2: unwrap()を使わずif letパターンで安全にチェック
         // 到達不可能なターゲットの場合はスキップ（パニックを防ぐ）
@@ -96,54 +87,46 @@ fn find_nearest_target(distances: &HashMap<(usize, usize), usize>, targets: &Vec
             }
         }
     }
-    
+
     // 修正点3: デバッグ用printlnを削除し、シンプルなreturnに変更
     nearest_target
 }
 
 fn main() {
     println!("=== BFS学習: 最も近い宝物を探す問題 ===\n");
-    
+
     // マップの定義
     // 'S' = スタート, 'T' = 宝物（ターゲット）, '#' = 壁, '.' = 通路
-    let grid = vec![
-        vec!['#', '#', '#', '#', '#', '#', '#', '#', '#'],
-        vec!['#', 'S', '.', '.', '#', 'T', '.', '.', '#'],
-        vec!['#', '#', '#', '.', '#', '.', '#', '.', '#'],
-        vec!['#', 'T', '.', '.', '.', '.', '#', '.', '#'],
-        vec!['#', '.', '#', '#', '#', '.', '#', '.', '#'],
-        vec!['#', '.', '.', '.', '.', '.', '.', '.', '#'],
-        vec!['#', '#', '#', '.', '#', '#', '#', 'T', '#'],
-        vec!['#', '#', '#', '#', '#', '#', '#', '#', '#'],
-    ];
-    
+    let grid = Grid::parse(
+        "\
+#########
+#S..#T..#
+###.#.#.#
+#T....#.#
+#.###.#.#
+#.......#
+###.###T#
+#########",
+    )
+    .unwrap();
+
     println!("マップ:");
-    for row in &grid {
-        println!("{}", row.iter().collect::<String>());
-    }
+    print!("{}", grid);
     println!();
-    
+
     // スタート地点とターゲット（宝物）の位置を探す
-    let mut start: (isize, isize) = (0, 0);
-    let mut targets = Vec::new();
-    
-    for i in 0..grid.len() {
-        for j in 0..grid[0].len() {
-            if grid[i][j] == 'S' {
-                start = (i as isize, j as isize);
-                println!("スタート: ({}, {})", i, j);
-            } else if grid[i][j] == 'T' {
-                targets.push((i, j));
-                println!("宝物{}: ({}, {})", targets.len(), i, j);
-            }
-        }
+    let start = grid.find(|&c| c == 'S').expect("スタートがありません");
+    println!("スタート: ({}, {})", start.0, start.1);
+    let targets = grid.find_all(|&c| c == 'T');
+    for (idx, target) in targets.iter().enumerate() {
+        println!("宝物{}: ({}, {})", idx + 1, target.0, target.1);
     }
     println!();
-    
+
     // TODO: 実装が完成したらコメントを外す
     let distances = bfs_distance_map(&grid, start, targets.len());
-    println!("{:?}",distances);
-    // 
+    println!("{:?}", distances);
+    //
     println!("各宝物への距離:");
     for (idx, target) in targets.iter().enumerate() {
         match distances.get(target) {
@@ -152,21 +135,56 @@ fn main() {
         }
     }
     println!();
-    // 
+    //
     match find_nearest_target(&distances, &targets) {
         Some((pos, dist)) => {
             println!("最も近い宝物: ({}, {}) [距離: {}]", pos.0, pos.1, dist);
-        },
+        }
         None => {
             println!("どの宝物にも到達できません");
         }
     }
-    
+
+    // 全てのマスについて「一番近い宝物」を多始点BFS1回で求める
+    println!("\n距離の地図（一番近い宝物までの歩数、10以上は '+'）:");
+    let field = nearest_target_field(&grid, &targets, |&c| c != '#');
+    let distance_map = Grid::from_fn(grid.rows(), grid.cols(), |row, col| {
+        match field.distance[(row, col)] {
+            Some(d) if d < 10 => char::from_digit(d as u32, 10).unwrap_or('+'),
+            Some(_) => '+',
+            None => grid[(row, col)],
+        }
+    });
+    print!("{}", distance_map);
+    println!("どの宝物に一番近いか（宝物の番号）:");
+    let owner_map = Grid::from_fn(grid.rows(), grid.cols(), |row, col| {
+        field.nearest[(row, col)]
+            .and_then(|target| targets.iter().position(|&t| t == target))
+            .map_or(grid[(row, col)], |i| {
+                char::from_digit(i as u32 + 1, 10).unwrap_or('?')
+            })
+    });
+    print!("{}", owner_map);
+    if let Some(((row, col), d)) = field.farthest() {
+        println!(
+            "どの宝物からも一番遠いマス: ({}, {}) [距離: {}]",
+            row, col, d
+        );
+    }
+
+    // 壁を無視した直線距離（ユークリッド距離変換）を濃淡で表示する
+    println!("\n直線距離のヒートマップ（近い '@' → 遠い ' '）:");
+    let euclid = euclidean_distance(&grid, |&c| c == 'T');
+    let shades = ['@', '%', '+', '-', '.', ' '];
+    let heatmap =
+        euclid.map(|d| d.map_or('?', |d| shades[(d.round() as usize).min(shades.len() - 1)]));
+    print!("{}", heatmap);
+
     println!("\n問題説明:");
     println!("- BFSで全マスへの最短距離を計算");
     println!("- 複数の宝物の中で最も近いものを見つける");
     println!("- これはPaizaでよく出る「最も近い目標を探す」パターン");
-    
+
     println!("\n実装のヒント:");
     println!("1. bfs_distance_map:");
     println!("   - 通常のBFSだが、ゴールで止まらない");
@@ -176,4 +194,4 @@ fn main() {
     println!("   - targets.iter()でループ");
     println!("   - distances.get()で距離を取得");
     println!("   - 最小値を更新していく");
-}
\ No newline at end of file
+}
@@ -0,0 +1,259 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use super::astar::SearchReport;
+use super::grid::Grid;
+use super::path::GridPath;
+use crate::trace::{NoTrace, SearchObserver, TraceCounter, TraceNode};
+
+// =============================================================================
+// ↔️ 双方向BFS
+// =============================================================================
+//
+// スタートからだけでなく、ゴールからも同時にBFSして、真ん中で出会ったら終わりです。
+// 距離 d の地点までのマスの数が b^d 個に増えるとき、
+//   片側のBFS … b^d 個
+//   双方向BFS … b^(d/2) 個 × 2
+// と、調べるマスがぐっと減ります。
+//
+//   S → → → → ●  ← ← ← ← G      ● = 出会った場所
+//
+// 注意点が2つあります。
+// 1. 出会ったら「その段」を最後まで見る
+//    最初に見つかった出会いが最短とは限らないので、その段で見つかった中の最小を選ぶ
+// 2. ゴール側は「逆向きの辺」をたどる
+//    有向グラフでは、ゴールから backward（どこから来られるか）で広げる
+//
+// 毎回、フロンティア（次に広げる段）が小さい側を1段だけ広げます。
+
+// 片側の探索の状態
+struct Side<N> {
+    // parent[ノード] = (直前のノード, 距離)（訪問済みの記録も兼ねる）
+    parent: HashMap<N, (Option<N>, usize)>,
+    frontier: Vec<N>,
+}
+
+impl<N: TraceNode + Eq + Hash> Side<N> {
+    fn new(start: N) -> Side<N> {
+        let mut parent = HashMap::new();
+        parent.insert(start, (None, 0));
+        Side {
+            parent,
+            frontier: vec![start],
+        }
+    }
+
+    fn distance(&self, node: N) -> Option<usize> {
+        self.parent.get(&node).map(|&(_, d)| d)
+    }
+
+    // フロンティアを1段広げる
+    // もう片側の訪問済みノードにぶつかったら、(出会ったノード, 合計の距離) の最小を返す
+    fn expand<F, I, O>(
+        &mut self,
+        other: &Side<N>,
+        next: &mut F,
+        observer: &mut O,
+    ) -> Option<(N, usize)>
+    where
+        F: FnMut(N) -> I,
+        I: IntoIterator<Item = N>,
+        O: SearchObserver<N>,
+    {
+        let mut best: Option<(N, usize)> = None;
+        let mut new_frontier = Vec::new();
+        for node in std::mem::take(&mut self.frontier) {
+            let d = self.parent[&node].1;
+            observer.on_pop(node, d as u64);
+            for to in next(node) {
+                if let Entry::Vacant(entry) = self.parent.entry(to) {
+                    entry.insert((Some(node), d + 1));
+                    observer.on_relax(node, to, d as u64 + 1);
+                    new_frontier.push(to);
+                }
+                if let Some(rest) = other.distance(to) {
+                    let total = d + 1 + rest;
+                    if best.is_none_or(|(_, b)| total < b) {
+                        best = Some((to, total));
+                    }
+                }
+            }
+        }
+        self.frontier = new_frontier;
+        best
+    }
+
+    // node から探索の始点まで親をたどる（node が先頭）
+    fn walk(&self, node: N) -> Vec<N> {
+        let mut nodes = vec![node];
+        let mut current = node;
+        while let Some(&(Some(prev), _)) = self.parent.get(&current) {
+            nodes.push(prev);
+            current = prev;
+        }
+        nodes
+    }
+}
+
+// 双方向BFS（ノードの型は自由）
+// forward(ノード) = そこから1手で行けるノード
+// backward(ノード) = 1手でそこに来られるノード（無向グラフなら forward と同じ）
+// スタートからゴールまでのノードの列を返す（到達できなければNone）
+pub fn bidirectional_search<N, F, B, FI, BI, O>(
+    start: N,
+    goal: N,
+    mut forward: F,
+    mut backward: B,
+    observer: &mut O,
+) -> Option<Vec<N>>
+where
+    N: TraceNode + Eq + Hash,
+    F: FnMut(N) -> FI,
+    FI: IntoIterator<Item = N>,
+    B: FnMut(N) -> BI,
+    BI: IntoIterator<Item = N>,
+    O: SearchObserver<N>,
+{
+    if start == goal {
+        return Some(vec![start]);
+    }
+    let mut from_start = Side::new(start);
+    let mut from_goal = Side::new(goal);
+
+    while !from_start.frontier.is_empty() && !from_goal.frontier.is_empty() {
+        // フロンティアが小さい側を広げる
+        let meeting = if from_start.frontier.len() <= from_goal.frontier.len() {
+            from_start.expand(&from_goal, &mut forward, observer)
+        } else {
+            from_goal.expand(&from_start, &mut backward, observer)
+        };
+        if let Some((meet, _)) = meeting {
+            // スタート → 出会った場所 と 出会った場所 → ゴール をつなぐ
+            let mut nodes = from_start.walk(meet);
+            nodes.reverse();
+            nodes.extend(from_goal.walk(meet).into_iter().skip(1));
+            return Some(nodes);
+        }
+    }
+
+    None
+}
+
+// 重みなしの有向グラフ（隣接リスト）で双方向BFS
+// ゴール側のために逆向きの隣接リストを作ってから探索する
+pub fn bidirectional_bfs_graph(
+    graph: &[Vec<usize>],
+    start: usize,
+    goal: usize,
+) -> Option<Vec<usize>> {
+    if start >= graph.len() || goal >= graph.len() {
+        return None;
+    }
+    let mut reverse = vec![Vec::new(); graph.len()];
+    for (from, edges) in graph.iter().enumerate() {
+        for &to in edges {
+            reverse[to].push(from);
+        }
+    }
+    bidirectional_search(
+        start,
+        goal,
+        |node| graph[node].iter().copied(),
+        |node| reverse[node].iter().copied(),
+        &mut NoTrace,
+    )
+}
+
+// 迷路（'#' = 壁）で双方向BFS
+// expanded は両側で広げたマスの合計（片側のBFSとの比較用）
+pub fn bidirectional_bfs<O: SearchObserver<(usize, usize)>>(
+    maze: &Grid<char>,
+    start: (usize, usize),
+    goal: (usize, usize),
+    observer: &mut O,
+) -> SearchReport {
+    // TraceCounter で広げたマスを数えながら、元のobserverにも伝える
+    let mut counted = (TraceCounter::default(), observer);
+    let neighbors = |pos| maze.neighbors4(pos).filter(|&next| maze[next] != '#');
+    let cells = bidirectional_search(start, goal, neighbors, neighbors, &mut counted);
+    let path = cells.map(|cells| GridPath {
+        cost: cells.len() as u64 - 1,
+        cells,
+    });
+    SearchReport {
+        path,
+        expanded: counted.0.pops,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dfs_bfs_practice::maze::bfs_shortest_distance;
+    use crate::dfs_bfs_practice::maze_gen::{generate_maze, MazeAlgorithm};
+    use crate::rng::XorShift64;
+
+    // 経路が隣り合うマスだけを通り、壁を通らないか
+    fn is_valid_path(maze: &Grid<char>, cells: &[(usize, usize)]) -> bool {
+        cells.iter().all(|&cell| maze[cell] != '#')
+            && cells
+                .windows(2)
+                .all(|w| w[0].0.abs_diff(w[1].0) + w[0].1.abs_diff(w[1].1) == 1)
+    }
+
+    #[test]
+    fn test_same_length_as_bfs_on_generated_mazes() {
+        for seed in 1..=5 {
+            for algorithm in MazeAlgorithm::ALL {
+                for braid in [0.0, 0.5] {
+                    let mut rng = XorShift64::new(seed);
+                    let maze = generate_maze(&mut rng, 21, 31, algorithm, braid).unwrap();
+                    let start = maze.find(|&c| c == 'S').unwrap();
+                    let goal = maze.find(|&c| c == 'G').unwrap();
+                    let expected = bfs_shortest_distance(&maze, start, goal, &mut NoTrace);
+                    let report = bidirectional_bfs(&maze, start, goal, &mut NoTrace);
+                    let path = report.path.unwrap();
+                    assert_eq!(
+                        Some(path.steps()),
+                        expected,
+                        "{:?} seed {}",
+                        algorithm,
+                        seed
+                    );
+                    assert_eq!(path.cells.first(), Some(&start));
+                    assert_eq!(path.cells.last(), Some(&goal));
+                    assert!(is_valid_path(&maze, &path.cells));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_expands_less_on_open_map() {
+        let mut open = Grid::new(60, 60, '.');
+        open[(0, 0)] = 'S';
+        open[(59, 59)] = 'G';
+        let mut one_sided = TraceCounter::default();
+        let steps = bfs_shortest_distance(&open, (0, 0), (59, 59), &mut one_sided);
+        let report = bidirectional_bfs(&open, (0, 0), (59, 59), &mut NoTrace);
+        assert_eq!(report.path.map(|p| p.steps()), steps);
+        assert!(report.expanded < one_sided.pops);
+
+        // 壁で分断されていれば到達できない
+        let walled = Grid::parse("S.#..\n..#.G").unwrap();
+        let report = bidirectional_bfs(&walled, (0, 0), (1, 4), &mut NoTrace);
+        assert_eq!(report.path, None);
+    }
+
+    #[test]
+    fn test_directed_graph() {
+        // 0 → 1 → 2 → 3 と、近道 0 → 4 → 3。3 → 0 には行けない
+        let graph = vec![vec![1, 4], vec![2], vec![3], vec![], vec![3]];
+        assert_eq!(bidirectional_bfs_graph(&graph, 0, 3), Some(vec![0, 4, 3]));
+        assert_eq!(bidirectional_bfs_graph(&graph, 1, 3), Some(vec![1, 2, 3]));
+        assert_eq!(bidirectional_bfs_graph(&graph, 3, 0), None);
+        assert_eq!(bidirectional_bfs_graph(&graph, 2, 2), Some(vec![2]));
+        assert_eq!(bidirectional_bfs_graph(&graph, 0, 9), None);
+    }
+}
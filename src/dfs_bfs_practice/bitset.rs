@@ -0,0 +1,277 @@
+use std::collections::{HashSet, VecDeque};
+
+use super::dfs::walk_dfs;
+use super::grid::{Grid, DIRECTIONS4};
+use super::topology::Orthogonal;
+
+// =============================================================================
+// 🧮 ビットで詰めたグリッド（巨大な地図用）
+// =============================================================================
+//
+// visited を HashSet<(usize, usize)> で持つと、1マスごとに
+//   キー16バイト + ハッシュ表の管理用の領域
+// を使い、調べるたびにハッシュ計算が走ります。
+// 10000×10000 の地図なら、それだけで数GBになることもあります。
+//
+// 「陸地か」「訪問済みか」は true / false だけなので、1マス1ビットで足ります。
+// u64 1つに64マス分を詰めて持つと、10000×10000 でも 12.5MB です。
+//
+//   マス (row, col) の番号 i = row * cols + col
+//   words[i / 64] の (i % 64) ビット目
+//
+// 探索の visited は VisitedSet トレイトで受け取るので、dfs.rs のDFSは
+// HashSet でも BitGrid でもそのまま動きます（島の数え上げは BitGrid を使う）。
+// ここの島の関数も、地図を BitGrid で持つ版として同じDFS（walk_dfs）を呼ぶだけです。
+
+// 訪問済みのマスの集まり
+pub trait VisitedSet {
+    fn contains(&self, pos: (usize, usize)) -> bool;
+    // 訪問済みにする。初めての訪問なら true を返す（HashSet::insert と同じ）
+    fn insert(&mut self, pos: (usize, usize)) -> bool;
+}
+
+impl VisitedSet for HashSet<(usize, usize)> {
+    fn contains(&self, pos: (usize, usize)) -> bool {
+        HashSet::contains(self, &pos)
+    }
+
+    fn insert(&mut self, pos: (usize, usize)) -> bool {
+        HashSet::insert(self, pos)
+    }
+}
+
+impl VisitedSet for BitGrid {
+    fn contains(&self, pos: (usize, usize)) -> bool {
+        self.get(pos)
+    }
+
+    fn insert(&mut self, pos: (usize, usize)) -> bool {
+        BitGrid::insert(self, pos)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitGrid {
+    rows: usize,
+    cols: usize,
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    // 全てのマスが false のグリッド
+    pub fn new(rows: usize, cols: usize) -> BitGrid {
+        BitGrid {
+            rows,
+            cols,
+            words: vec![0; (rows * cols).div_ceil(64)],
+        }
+    }
+
+    // 各マスの値を関数で決める
+    pub fn from_fn<F: FnMut(usize, usize) -> bool>(rows: usize, cols: usize, mut f: F) -> BitGrid {
+        let mut bits = BitGrid::new(rows, cols);
+        for row in 0..rows {
+            for col in 0..cols {
+                if f(row, col) {
+                    bits.insert((row, col));
+                }
+            }
+        }
+        bits
+    }
+
+    // Grid<T> の各マスを条件で true / false にする
+    pub fn from_grid<T, F: Fn(&T) -> bool>(grid: &Grid<T>, pred: F) -> BitGrid {
+        BitGrid::from_fn(grid.rows(), grid.cols(), |row, col| pred(&grid[(row, col)]))
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    fn index_of(&self, (row, col): (usize, usize)) -> usize {
+        assert!(
+            row < self.rows && col < self.cols,
+            "({}, {}) はグリッドの範囲外です",
+            row,
+            col
+        );
+        row * self.cols + col
+    }
+
+    pub fn get(&self, pos: (usize, usize)) -> bool {
+        let i = self.index_of(pos);
+        self.words[i / 64] >> (i % 64) & 1 == 1
+    }
+
+    pub fn set(&mut self, pos: (usize, usize), value: bool) {
+        let i = self.index_of(pos);
+        if value {
+            self.words[i / 64] |= 1 << (i % 64);
+        } else {
+            self.words[i / 64] &= !(1 << (i % 64));
+        }
+    }
+
+    // true にする。もともと false だったら true を返す（HashSet::insert と同じ）
+    pub fn insert(&mut self, pos: (usize, usize)) -> bool {
+        let was_set = self.get(pos);
+        self.set(pos, true);
+        !was_set
+    }
+
+    // true のマスの数
+    pub fn count_ones(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    // ビットを入れている領域のバイト数
+    pub fn bytes(&self) -> usize {
+        self.words.len() * std::mem::size_of::<u64>()
+    }
+
+    // 上下左右の隣のマス（範囲外は含まない）
+    pub fn neighbors4(&self, (row, col): (usize, usize)) -> impl Iterator<Item = (usize, usize)> {
+        let (rows, cols) = (self.rows, self.cols);
+        DIRECTIONS4.iter().filter_map(move |&(dr, dc)| {
+            let r = row.checked_add_signed(dr)?;
+            let c = col.checked_add_signed(dc)?;
+            (r < rows && c < cols).then_some((r, c))
+        })
+    }
+}
+
+// 通れるマス（true）だけを通って、startからgoalまでの最短距離をBFSで求める
+pub fn bfs_distance_bits(
+    passable: &BitGrid,
+    start: (usize, usize),
+    goal: (usize, usize),
+) -> Option<usize> {
+    if !passable.get(start) || !passable.get(goal) {
+        return None;
+    }
+    let mut visited = BitGrid::new(passable.rows(), passable.cols());
+    let mut queue = VecDeque::new();
+    visited.insert(start);
+    queue.push_back((start, 0));
+
+    while let Some((current, distance)) = queue.pop_front() {
+        if current == goal {
+            return Some(distance);
+        }
+        for next in passable.neighbors4(current) {
+            if passable.get(next) && visited.insert(next) {
+                queue.push_back((next, distance + 1));
+            }
+        }
+    }
+
+    None
+}
+
+// startを含む島をDFSで訪問し、面積を返す（visited に訪問済みを記録する）
+pub fn explore_island_bits<V: VisitedSet>(
+    land: &BitGrid,
+    visited: &mut V,
+    start: (usize, usize),
+) -> usize {
+    let mut area = 0;
+    walk_dfs(
+        land.rows(),
+        land.cols(),
+        &Orthogonal,
+        visited,
+        start,
+        |pos| land.get(pos),
+        |_| area += 1,
+    );
+    area
+}
+
+// 島の数と最大面積
+pub fn count_islands_bits(land: &BitGrid) -> (usize, usize) {
+    let mut visited = BitGrid::new(land.rows(), land.cols());
+    let (mut count, mut max_area) = (0, 0);
+    for row in 0..land.rows() {
+        for col in 0..land.cols() {
+            if !land.get((row, col)) || visited.get((row, col)) {
+                continue;
+            }
+            let area = explore_island_bits(land, &mut visited, (row, col));
+            count += 1;
+            max_area = max_area.max(area);
+        }
+    }
+    (count, max_area)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dfs_bfs_practice::dfs::{count_islands_dfs, explore_island_dfs};
+    use crate::dfs_bfs_practice::maze::bfs_shortest_distance;
+    use crate::rng::XorShift64;
+    use crate::trace::NoTrace;
+
+    #[test]
+    fn test_bits() {
+        // 64の倍数でない大きさで、ワードの境目をまたぐ
+        let mut bits = BitGrid::new(3, 43);
+        // 3 × 43 = 129ビット → u64が3つ
+        assert_eq!(bits.bytes(), 3 * 8);
+        assert!(bits.insert((1, 20)));
+        assert!(!bits.insert((1, 20)));
+        bits.set((2, 42), true);
+        assert!(bits.get((1, 20)) && bits.get((2, 42)) && !bits.get((1, 21)));
+        assert_eq!(bits.count_ones(), 2);
+        bits.set((1, 20), false);
+        assert_eq!(bits.count_ones(), 1);
+    }
+
+    #[test]
+    fn test_islands_match_hashset_version() {
+        let mut rng = XorShift64::new(5);
+        for &(rows, cols, density) in &[(1, 1, 0.5), (7, 70, 0.5), (40, 33, 0.6), (64, 64, 0.3)] {
+            let grid = Grid::from_fn(rows, cols, |_, _| rng.chance(density));
+            let land = BitGrid::from_grid(&grid, |&b| b);
+            assert_eq!(land.count_ones(), grid.find_all(|&b| b).len());
+            assert_eq!(count_islands_bits(&land), count_islands_dfs(&grid, |&b| b));
+            // 同じDFSに HashSet と BitGrid のどちらの visited を渡しても、訪問順は同じ
+            if let Some(start) = grid.find(|&b| b) {
+                let mut hashed = HashSet::new();
+                let mut bits = BitGrid::new(rows, cols);
+                assert_eq!(
+                    explore_island_dfs(&grid, &mut hashed, start, |&b| b),
+                    explore_island_dfs(&grid, &mut bits, start, |&b| b)
+                );
+                assert_eq!(hashed.len(), bits.count_ones());
+                assert_eq!(
+                    explore_island_bits(&land, &mut HashSet::new(), start),
+                    hashed.len()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_bfs_matches_grid_bfs() {
+        let mut rng = XorShift64::new(9);
+        for _ in 0..20 {
+            let mut maze = Grid::from_fn(15, 70, |_, _| if rng.chance(0.3) { '#' } else { '.' });
+            maze[(0, 0)] = 'S';
+            maze[(14, 69)] = 'G';
+            let passable = BitGrid::from_grid(&maze, |&c| c != '#');
+            assert_eq!(
+                bfs_distance_bits(&passable, (0, 0), (14, 69)),
+                bfs_shortest_distance(&maze, (0, 0), (14, 69), &mut NoTrace)
+            );
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use std::collections::HashSet;
+
+use super::bitset::{BitGrid, VisitedSet};
+use super::grid::Grid;
+use super::topology::{Orthogonal, Topology};
+
+// =============================================================================
+// 📚 スタックを使うDFS（再帰しないDFS）
+// =============================================================================
+//
+// 再帰のDFSは陸地1マスごとに関数呼び出しが1段深くなります。
+// 2000×2000 が全部陸地の地図では400万段になり、
+// 標準のスレッドのスタック（数MB）を使い切ってプログラムが落ちます。
+//
+// ここでは「関数呼び出しのスタック」の代わりに Vec を自前のスタックとして使います。
+// スタックの各要素は再帰の1回分の呼び出しで、
+//   (今いるマス, 次に調べる隣の番号)
+// だけを覚えておきます。隣のマスの一覧は一番上の要素の分だけを1つの Vec に持ち、
+// 再帰から戻ってきたときに戻った先のマスについて作り直します。
+// 方向を1つ調べるごとに番号を進め、
+// 全方向を調べ終わったら pop する（= 再帰から戻る）ので、
+// 訪問する順番は下の explore_island_dfs_recursive と完全に同じになります。
+//
+// 隣を調べる順番は Grid::neighbors4 の「下・右・上・左」で、
+// island_counter_dfs.rs や island_exploration.rs の再帰の練習と同じなので、訪問順もそれらと同じです。
+//
+// Vec はヒープに置かれるので、島がどれだけ大きくてもスタックは溢れません。
+//
+// 隣のマスは Topology（topology.rs）に聞くので、8方向・六角形・トーラスでも同じコードで動きます。
+//
+// visited は VisitedSet（bitset.rs）なら何でもよく、HashSet でも BitGrid でも同じコードで動きます。
+// 島の数え上げでは 1マス1ビットの BitGrid を使います。
+
+// startを含む島をDFSで訪問し、訪問したマスを訪問順に返す（面積 = 返り値の長さ）
+// visited に訪問済みのマスを記録する（既に訪問済みのマスは調べない）
+pub fn explore_island_dfs<T, V, F>(
+    grid: &Grid<T>,
+    visited: &mut V,
+    start: (usize, usize),
+    is_land: F,
+) -> Vec<(usize, usize)>
+where
+    V: VisitedSet,
+    F: Fn(&T) -> bool,
+{
+    explore_island_dfs_on(grid, &Orthogonal, visited, start, is_land)
+}
+
+// つながり方（トポロジー）を指定できるスタック版DFS
+pub fn explore_island_dfs_on<T, Topo, V, F>(
+    grid: &Grid<T>,
+    topology: &Topo,
+    visited: &mut V,
+    start: (usize, usize),
+    is_land: F,
+) -> Vec<(usize, usize)>
+where
+    Topo: Topology + ?Sized,
+    V: VisitedSet,
+    F: Fn(&T) -> bool,
+{
+    let mut order = Vec::new();
+    let open = |pos: (usize, usize)| is_land(&grid[pos]);
+    walk_dfs(
+        grid.rows(),
+        grid.cols(),
+        topology,
+        visited,
+        start,
+        open,
+        |pos| order.push(pos),
+    );
+    order
+}
+
+// スタック版DFSの本体
+// rows × cols の地図で open なマスだけを通って start から進み、訪問したマスを順に visit に渡す
+// 地図の形（Grid / BitGrid）にも visited の形にもよらない
+pub fn walk_dfs<Topo, V, O, E>(
+    rows: usize,
+    cols: usize,
+    topology: &Topo,
+    visited: &mut V,
+    start: (usize, usize),
+    open: O,
+    mut visit: E,
+) where
+    Topo: Topology + ?Sized,
+    V: VisitedSet,
+    O: Fn((usize, usize)) -> bool,
+    E: FnMut((usize, usize)),
+{
+    visited.insert(start);
+    visit(start);
+    // 再帰の呼び出し1回分 = (マス, 次に調べる隣の番号)
+    let mut stack = vec![(start, 0)];
+    // スタックの一番上のマスの、隣の陸地の一覧（neighbors_of のマスの分）
+    let mut neighbors = Vec::new();
+    let mut neighbors_of = None;
+
+    while let Some((pos, index)) = stack.last_mut() {
+        if neighbors_of != Some(*pos) {
+            topology.neighbors_into(rows, cols, *pos, &open, &mut neighbors);
+            neighbors.retain(|&cell| open(cell));
+            neighbors_of = Some(*pos);
+        }
+        let Some(&next) = neighbors.get(*index) else {
+            // 全方向を調べ終わった（再帰から戻る）
+            stack.pop();
+            continue;
+        };
+        *index += 1;
+
+        if visited.insert(next) {
+            // 再帰呼び出しの代わりにスタックに積む
+            visit(next);
+            stack.push((next, 0));
+        }
+    }
+}
+
+// 比較用: 再帰版のDFS（小さい地図でだけ使う）
+pub fn explore_island_dfs_recursive<T, F>(
+    grid: &Grid<T>,
+    visited: &mut HashSet<(usize, usize)>,
+    start: (usize, usize),
+    is_land: F,
+) -> Vec<(usize, usize)>
+where
+    F: Fn(&T) -> bool,
+{
+    fn visit<T, F: Fn(&T) -> bool>(
+        grid: &Grid<T>,
+        visited: &mut HashSet<(usize, usize)>,
+        current: (usize, usize),
+        is_land: &F,
+        order: &mut Vec<(usize, usize)>,
+    ) {
+        visited.insert(current);
+        order.push(current);
+        for next in grid.neighbors4(current) {
+            if is_land(&grid[next]) && !visited.contains(&next) {
+                visit(grid, visited, next, is_land, order);
+            }
+        }
+    }
+
+    let mut order = Vec::new();
+    visit(grid, visited, start, &is_land, &mut order);
+    order
+}
+
+// 島の数と最大面積をスタック版DFSで求める
+pub fn count_islands_dfs<T, F>(grid: &Grid<T>, is_land: F) -> (usize, usize)
+where
+    F: Fn(&T) -> bool,
+{
+    count_islands_on(grid, &Orthogonal, is_land)
+}
+
+// つながり方（トポロジー）を指定して、島の数と最大面積を求める
+pub fn count_islands_on<T, Topo, F>(grid: &Grid<T>, topology: &Topo, is_land: F) -> (usize, usize)
+where
+    Topo: Topology + ?Sized,
+    F: Fn(&T) -> bool,
+{
+    let mut visited = BitGrid::new(grid.rows(), grid.cols());
+    let (mut count, mut max_area) = (0, 0);
+    for pos in grid.positions() {
+        if visited.contains(pos) || !is_land(&grid[pos]) {
+            continue;
+        }
+        let mut area = 0;
+        let open = |cell: (usize, usize)| is_land(&grid[cell]);
+        walk_dfs(
+            grid.rows(),
+            grid.cols(),
+            topology,
+            &mut visited,
+            pos,
+            open,
+            |_| area += 1,
+        );
+        count += 1;
+        max_area = max_area.max(area);
+    }
+    (count, max_area)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_same_order_as_recursive() {
+        let grid = Grid::parse(
+            "\
+##.##
+#..#.
+####.
+..#.#
+###.#",
+        )
+        .unwrap();
+        for start in grid.find_all(|&c| c == '#') {
+            let mut visited1 = HashSet::new();
+            let mut visited2 = HashSet::new();
+            let iterative = explore_island_dfs(&grid, &mut visited1, start, |&c| c == '#');
+            let recursive =
+                explore_island_dfs_recursive(&grid, &mut visited2, start, |&c| c == '#');
+            assert_eq!(iterative, recursive);
+            assert_eq!(visited1, visited2);
+        }
+        assert_eq!(count_islands_dfs(&grid, |&c| c == '#'), (2, 14));
+    }
+
+    #[test]
+    fn test_large_solid_island_in_small_stack() {
+        // 2000×2000 が全部陸地（再帰版なら400万段の再帰になり、小さいスタックは確実に溢れる）
+        // 256KBのスタックのスレッドで実行しても溢れないことを確認する
+        let result = thread::Builder::new()
+            .stack_size(256 * 1024)
+            .spawn(|| {
+                let grid = Grid::new(2000, 2000, true);
+                count_islands_dfs(&grid, |&land| land)
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+        assert_eq!(result, (1, 2000 * 2000));
+    }
+}
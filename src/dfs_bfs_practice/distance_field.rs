@@ -0,0 +1,249 @@
+use super::grid::Grid;
+use super::multi_source_bfs::multi_source_bfs;
+
+// =============================================================================
+// 🗺️ 距離の地図（一番近い目標までの距離を全マスについて）
+// =============================================================================
+//
+// 「スタートから各目標までの距離」ではなく、
+// 「各マスから一番近い目標までの距離と、その目標はどれか」を全マスについて求めます。
+// 目標を全部スタートにした多始点BFSなら1回で済みます。
+//
+//   目標 T が2つの地図        距離の地図
+//   T....                     01234
+//   .....                     12343
+//   ....T                     23210  ← 右下のTに近いマスは右下のTを指す
+//
+// BFSの距離は「壁をよけて歩く歩数」です。
+// 壁を無視した「直線距離（ユークリッド距離）」が欲しいときは、
+// 下の euclidean_distance（距離変換）を使います。
+
+// 一番近い目標の地図
+#[derive(Debug, Clone, PartialEq)]
+pub struct NearestTargetField {
+    // 一番近い目標までの歩数（どの目標にも行けないマスはNone）
+    pub distance: Grid<Option<usize>>,
+    // 一番近い目標の座標（同じ距離なら先に見つかった方）
+    pub nearest: Grid<Option<(usize, usize)>>,
+}
+
+impl NearestTargetField {
+    // cellから一番近い目標と、その距離
+    pub fn at(&self, cell: (usize, usize)) -> Option<((usize, usize), usize)> {
+        Some((self.nearest[cell]?, self.distance[cell]?))
+    }
+
+    // どの目標からも一番遠いマスと、その距離（新しい目標を置く場所の候補）
+    pub fn farthest(&self) -> Option<((usize, usize), usize)> {
+        self.distance
+            .iter()
+            .filter_map(|(pos, d)| d.map(|d| (pos, d)))
+            .max_by_key(|&((row, col), d)| (d, std::cmp::Reverse((row, col))))
+    }
+}
+
+// 全ての目標から同時にBFSして、一番近い目標の地図を作る
+// passable(値) が true のマスだけを通る（目標のマスは必ず含める）
+pub fn nearest_target_field<T, F>(
+    grid: &Grid<T>,
+    targets: &[(usize, usize)],
+    passable: F,
+) -> NearestTargetField
+where
+    F: Fn(&T) -> bool,
+{
+    let result = multi_source_bfs(grid, targets, |_, cell| passable(cell));
+    NearestTargetField {
+        distance: result.distance,
+        nearest: result.nearest.map(|index| index.map(|i| targets[i])),
+    }
+}
+
+// =============================================================================
+// 📐 ユークリッド距離変換
+// =============================================================================
+//
+// 各マスから一番近い目標マスまでの直線距離を、全マスについて正確に求めます。
+// 目標ごとに全マスを調べると (マス数 × 目標数) ですが、
+// 「二乗距離 = 縦の差の二乗 + 横の差の二乗」と分けられることを使うと、
+// 列ごと → 行ごと の2回の1次元処理で (マス数) に比例する時間で済みます。
+//
+// 1次元の処理（Felzenszwalb と Huttenlocher の方法）:
+//   位置 q にある値 f(q) を、放物線 (x - q)² + f(q) と考える
+//   全ての放物線の「下側の輪郭」が、各 x での最小値になる
+//
+//   f:  ∞  ∞  0  ∞  ∞  ∞  0        → 答え: 4 1 0 1 4 1 0
+//
+// 距離は二乗のまま整数で計算するので誤差がありません。
+
+// 各マスから一番近い目標マスまでの二乗距離（目標が1つもなければ全てNone）
+pub fn squared_euclidean_distance<T, F>(grid: &Grid<T>, is_target: F) -> Grid<Option<u64>>
+where
+    F: Fn(&T) -> bool,
+{
+    let (rows, cols) = (grid.rows(), grid.cols());
+    // 列ごと: 同じ列の一番近い目標までの二乗距離
+    let mut by_column = Grid::new(rows, cols, None);
+    for col in 0..cols {
+        let f: Vec<Option<u64>> = (0..rows)
+            .map(|row| is_target(&grid[(row, col)]).then_some(0))
+            .collect();
+        for (row, d) in lower_envelope(&f).into_iter().enumerate() {
+            by_column[(row, col)] = d;
+        }
+    }
+    // 行ごと: 列ごとの結果を使って、2次元の二乗距離にする
+    let mut result = Grid::new(rows, cols, None);
+    for row in 0..rows {
+        for (col, d) in lower_envelope(by_column.row(row)).into_iter().enumerate() {
+            result[(row, col)] = d;
+        }
+    }
+    result
+}
+
+// 各マスから一番近い目標マスまでの直線距離
+pub fn euclidean_distance<T, F>(grid: &Grid<T>, is_target: F) -> Grid<Option<f64>>
+where
+    F: Fn(&T) -> bool,
+{
+    squared_euclidean_distance(grid, is_target).map(|d| d.map(|d| (d as f64).sqrt()))
+}
+
+// 1次元の二乗距離変換: 各 x について min_q { (x - q)² + f(q) }
+fn lower_envelope(f: &[Option<u64>]) -> Vec<Option<u64>> {
+    // 放物線 q と p（p < q）の交点の x 座標
+    let intersect = |p: usize, q: usize, fp: u64, fq: u64| {
+        ((fq + (q * q) as u64) as f64 - (fp + (p * p) as u64) as f64) / (2 * (q - p)) as f64
+    };
+
+    // 輪郭を作っている放物線と、その放物線が一番下になり始める x
+    let mut sites: Vec<(usize, u64)> = Vec::new();
+    let mut starts: Vec<f64> = Vec::new();
+    for (q, fq) in f
+        .iter()
+        .enumerate()
+        .filter_map(|(q, fq)| fq.map(|fq| (q, fq)))
+    {
+        loop {
+            let Some(&(p, fp)) = sites.last() else {
+                sites.push((q, fq));
+                starts.push(f64::NEG_INFINITY);
+                break;
+            };
+            let s = intersect(p, q, fp, fq);
+            if s <= *starts.last().unwrap_or(&f64::NEG_INFINITY) {
+                // 新しい放物線が前の放物線を完全に隠す
+                sites.pop();
+                starts.pop();
+            } else {
+                sites.push((q, fq));
+                starts.push(s);
+                break;
+            }
+        }
+    }
+    if sites.is_empty() {
+        return vec![None; f.len()];
+    }
+
+    let mut k = 0;
+    (0..f.len())
+        .map(|x| {
+            while k + 1 < sites.len() && starts[k + 1] <= x as f64 {
+                k += 1;
+            }
+            let (q, fq) = sites[k];
+            let dx = x.abs_diff(q) as u64;
+            Some(dx * dx + fq)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dfs_bfs_practice::maze::bfs_shortest_distance;
+    use crate::rng::XorShift64;
+    use crate::trace::NoTrace;
+
+    #[test]
+    fn test_nearest_target_field_matches_single_bfs() {
+        let grid = Grid::parse(
+            "\
+#########
+#S..#T..#
+###.#.#.#
+#T....#.#
+#.###.#.#
+#.......#
+###.###T#
+#########",
+        )
+        .unwrap();
+        let targets = grid.find_all(|&c| c == 'T');
+        let field = nearest_target_field(&grid, &targets, |&c| c != '#');
+        for cell in grid.positions().filter(|&cell| grid[cell] != '#') {
+            // 目標ごとのBFSの最小値と一致する
+            let expected = targets
+                .iter()
+                .filter_map(|&t| bfs_shortest_distance(&grid, cell, t, &mut NoTrace))
+                .min();
+            assert_eq!(field.distance[cell], expected, "{:?}", cell);
+            let (target, d) = field.at(cell).unwrap();
+            assert_eq!(
+                bfs_shortest_distance(&grid, cell, target, &mut NoTrace),
+                Some(d)
+            );
+        }
+        assert_eq!(field.at((1, 1)), Some(((3, 1), 6)));
+        assert_eq!(field.at((0, 0)), None);
+        assert_eq!(field.farthest(), Some(((1, 1), 6)));
+    }
+
+    #[test]
+    fn test_euclidean_distance_matches_brute_force() {
+        let mut rng = XorShift64::new(11);
+        for &(rows, cols, density) in &[
+            (1, 1, 0.5),
+            (1, 12, 0.2),
+            (9, 1, 0.2),
+            (17, 23, 0.05),
+            (30, 30, 0.01),
+        ] {
+            let grid = Grid::from_fn(rows, cols, |_, _| rng.chance(density));
+            let targets = grid.find_all(|&t| t);
+            let squared = squared_euclidean_distance(&grid, |&t| t);
+            for (row, col) in grid.positions() {
+                let expected = targets
+                    .iter()
+                    .map(|&(r, c)| (row.abs_diff(r).pow(2) + col.abs_diff(c).pow(2)) as u64)
+                    .min();
+                assert_eq!(
+                    squared[(row, col)],
+                    expected,
+                    "{}×{} ({}, {})",
+                    rows,
+                    cols,
+                    row,
+                    col
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_euclidean_distance_ignores_walls() {
+        // 直線距離なので、BFSと違って壁の向こうでも近い
+        let grid = Grid::parse("T#.\n.#.\n...").unwrap();
+        let distance = euclidean_distance(&grid, |&c| c == 'T');
+        assert_eq!(distance[(0, 2)], Some(2.0));
+        assert_eq!(distance[(2, 2)], Some(8f64.sqrt()));
+        let field = nearest_target_field(&grid, &[(0, 0)], |&c| c != '#');
+        assert_eq!(field.distance[(0, 2)], Some(6));
+        assert!(euclidean_distance(&grid, |&c| c == 'X')
+            .cells()
+            .iter()
+            .all(|d| d.is_none()));
+    }
+}
@@ -0,0 +1,393 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::ops::{Index, IndexMut};
+
+// =============================================================================
+// 🧱 Grid<T>: 2次元グリッドの共通型
+// =============================================================================
+//
+// これまで各問題で Vec<Vec<char>> を使い、毎回 isize にキャストして
+// 範囲チェックを書いていました。行の長さがバラバラだと
+// grid[row].len() - 1 がアンダーフローすることもありました。
+//
+// Grid<T> はセルを1本のVecに行優先（row-major）で並べて持ちます。
+//   index = row * cols + col
+// 範囲チェック済みの neighbors4 / neighbors8 を使えば、
+// 隣のマスを調べるときにキャストを書く必要はありません。
+
+// 4方向（下、右、上、左）
+pub const DIRECTIONS4: [(isize, isize); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+
+// 8方向（4方向 + 斜め）
+pub const DIRECTIONS8: [(isize, isize); 8] = [
+    (1, 0),
+    (0, 1),
+    (-1, 0),
+    (0, -1),
+    (1, 1),
+    (1, -1),
+    (-1, 1),
+    (-1, -1),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    rows: usize,
+    cols: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    // 全てのマスを同じ値で埋めたグリッドを作る
+    pub fn new(rows: usize, cols: usize, fill: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            rows,
+            cols,
+            cells: vec![fill; rows * cols],
+        }
+    }
+
+    // 各マスの値を関数で決めてグリッドを作る
+    pub fn from_fn<F: FnMut(usize, usize) -> T>(rows: usize, cols: usize, mut f: F) -> Grid<T> {
+        let mut cells = Vec::with_capacity(rows * cols);
+        for row in 0..rows {
+            for col in 0..cols {
+                cells.push(f(row, col));
+            }
+        }
+        Grid { rows, cols, cells }
+    }
+
+    // Vec<Vec<T>> から変換する（行の長さが揃っていなければエラー）
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Grid<T>, String> {
+        let cols = rows.first().map_or(0, |row| row.len());
+        let height = rows.len();
+        let mut cells = Vec::with_capacity(height * cols);
+        for (i, row) in rows.into_iter().enumerate() {
+            if row.len() != cols {
+                return Err(format!(
+                    "{}行目の長さが{}です（1行目は{}）",
+                    i + 1,
+                    row.len(),
+                    cols
+                ));
+            }
+            cells.extend(row);
+        }
+        Ok(Grid {
+            rows: height,
+            cols,
+            cells,
+        })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    // (row, col) を1次元のインデックスに変換する
+    pub fn index_of(&self, (row, col): (usize, usize)) -> usize {
+        row * self.cols + col
+    }
+
+    // 1次元のインデックスを (row, col) に戻す
+    pub fn position_of(&self, index: usize) -> (usize, usize) {
+        (index / self.cols, index % self.cols)
+    }
+
+    // 符号付きの座標がグリッドの中にあれば (row, col) にして返す
+    pub fn checked_position(&self, row: isize, col: isize) -> Option<(usize, usize)> {
+        if row < 0 || col < 0 || row as usize >= self.rows || col as usize >= self.cols {
+            None
+        } else {
+            Some((row as usize, col as usize))
+        }
+    }
+
+    pub fn get(&self, (row, col): (usize, usize)) -> Option<&T> {
+        if row < self.rows && col < self.cols {
+            Some(&self.cells[row * self.cols + col])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, (row, col): (usize, usize)) -> Option<&mut T> {
+        if row < self.rows && col < self.cols {
+            Some(&mut self.cells[row * self.cols + col])
+        } else {
+            None
+        }
+    }
+
+    // 1行分のスライス
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.cols..(row + 1) * self.cols]
+    }
+
+    // 全セルを行優先で並べたスライス
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    // 全ての座標（左上から行ごとに）
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let cols = self.cols;
+        (0..self.rows * cols).map(move |i| (i / cols, i % cols))
+    }
+
+    // (座標, 値) の組を行優先で
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> + '_ {
+        self.positions().zip(self.cells.iter())
+    }
+
+    // 条件を満たす最初のマス
+    pub fn find<F: Fn(&T) -> bool>(&self, pred: F) -> Option<(usize, usize)> {
+        self.cells
+            .iter()
+            .position(pred)
+            .map(|i| self.position_of(i))
+    }
+
+    // 条件を満たす全てのマス
+    pub fn find_all<F: Fn(&T) -> bool>(&self, pred: F) -> Vec<(usize, usize)> {
+        self.iter()
+            .filter(|(_, cell)| pred(cell))
+            .map(|(pos, _)| pos)
+            .collect()
+    }
+
+    // 各マスを変換した新しいグリッド
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> Grid<U> {
+        Grid {
+            rows: self.rows,
+            cols: self.cols,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    // 指定した方向の一覧から、範囲内にある隣のマスを返す
+    pub fn neighbors_in<'a>(
+        &self,
+        (row, col): (usize, usize),
+        directions: &'a [(isize, isize)],
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        let (rows, cols) = (self.rows, self.cols);
+        directions.iter().filter_map(move |&(dr, dc)| {
+            let r = row.checked_add_signed(dr)?;
+            let c = col.checked_add_signed(dc)?;
+            if r < rows && c < cols {
+                Some((r, c))
+            } else {
+                None
+            }
+        })
+    }
+
+    // 上下左右の隣のマス（範囲外は含まない）
+    pub fn neighbors4(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> {
+        self.neighbors_in(pos, &DIRECTIONS4)
+    }
+
+    // 斜めを含む8方向の隣のマス（範囲外は含まない）
+    pub fn neighbors8(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> {
+        self.neighbors_in(pos, &DIRECTIONS8)
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        assert!(
+            row < self.rows && col < self.cols,
+            "({}, {}) はグリッドの範囲外です",
+            row,
+            col
+        );
+        &self.cells[row * self.cols + col]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        assert!(
+            row < self.rows && col < self.cols,
+            "({}, {}) はグリッドの範囲外です",
+            row,
+            col
+        );
+        &mut self.cells[row * self.cols + col]
+    }
+}
+
+// 1行ずつ、セルを区切らずに並べて表示する（文字のグリッドならそのまま地図になる）
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in 0..self.rows {
+            for cell in self.row(row) {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+// =============================================================================
+// テキストからの読み込み
+// =============================================================================
+//
+// 文字 → セルの値 の対応表（凡例）を指定してテキストを読み込みます。
+// 例: '#' → true（陸地）, '.' → false（海）
+//     let legend = Legend::new().with('#', true).with('.', false);
+//     let land = Grid::parse_with(text, &legend)?;
+
+#[derive(Debug, Clone)]
+pub struct Legend<T> {
+    map: HashMap<char, T>,
+    default: Option<T>,
+}
+
+impl<T: Clone> Legend<T> {
+    pub fn new() -> Legend<T> {
+        Legend {
+            map: HashMap::new(),
+            default: None,
+        }
+    }
+
+    // 文字chをvalueとして読む
+    pub fn with(mut self, ch: char, value: T) -> Legend<T> {
+        self.map.insert(ch, value);
+        self
+    }
+
+    // 凡例にない文字をvalueとして読む（指定しなければエラー）
+    pub fn with_default(mut self, value: T) -> Legend<T> {
+        self.default = Some(value);
+        self
+    }
+
+    pub fn lookup(&self, ch: char) -> Option<T> {
+        self.map.get(&ch).or(self.default.as_ref()).cloned()
+    }
+}
+
+impl<T: Clone> Default for Legend<T> {
+    fn default() -> Legend<T> {
+        Legend::new()
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    // 凡例を使ってテキストを読み込む
+    // 前後の空行は無視し、行の長さが揃っていなければエラー
+    pub fn parse_with(text: &str, legend: &Legend<T>) -> Result<Grid<T>, String> {
+        Grid::parse_cells(text, |ch| legend.lookup(ch))
+    }
+
+    // ファイルから凡例を使って読み込む
+    pub fn load_with(path: &str, legend: &Legend<T>) -> Result<Grid<T>, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("ファイルを開けません({}): {}", path, e))?;
+        Grid::parse_with(&text, legend)
+    }
+}
+
+impl<T> Grid<T> {
+    // 1文字ずつ変換しながらテキストを読み込む（変換できない文字があればエラー）
+    fn parse_cells<F: Fn(char) -> Option<T>>(text: &str, convert: F) -> Result<Grid<T>, String> {
+        let lines: Vec<&str> = text
+            .lines()
+            .map(|line| line.trim_end_matches('\r'))
+            .collect();
+        let first = lines
+            .iter()
+            .position(|line| !line.is_empty())
+            .unwrap_or(lines.len());
+        let last = lines
+            .iter()
+            .rposition(|line| !line.is_empty())
+            .map_or(first, |i| i + 1);
+
+        let mut rows = Vec::with_capacity(last - first);
+        for (i, line) in lines[first..last].iter().enumerate() {
+            let mut row = Vec::with_capacity(line.len());
+            for (j, ch) in line.chars().enumerate() {
+                let value = convert(ch).ok_or_else(|| {
+                    format!(
+                        "{}行目{}文字目: 凡例にない文字 '{}' です",
+                        first + i + 1,
+                        j + 1,
+                        ch
+                    )
+                })?;
+                row.push(value);
+            }
+            rows.push(row);
+        }
+        Grid::from_rows(rows)
+    }
+}
+
+impl Grid<char> {
+    // 文字をそのままセルにして読み込む
+    pub fn parse(text: &str) -> Result<Grid<char>, String> {
+        Grid::parse_cells(text, Some)
+    }
+
+    // ファイルから文字のグリッドを読み込む
+    pub fn load(path: &str) -> Result<Grid<char>, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("ファイルを開けません({}): {}", path, e))?;
+        Grid::parse(&text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_display_round_trip() {
+        let text = "S.#\n..G\n";
+        let grid = Grid::parse(text).unwrap();
+        assert_eq!((grid.rows(), grid.cols()), (2, 3));
+        assert_eq!(grid[(1, 2)], 'G');
+        assert_eq!(grid.find(|&c| c == 'S'), Some((0, 0)));
+        assert_eq!(grid.to_string(), text);
+    }
+
+    #[test]
+    fn test_ragged_rows_are_rejected() {
+        assert!(Grid::parse("###\n#\n###").is_err());
+        assert!(Grid::parse("###\n\n###").is_err());
+    }
+
+    #[test]
+    fn test_parse_with_legend() {
+        let legend = Legend::new().with('#', true).with('.', false);
+        let grid = Grid::parse_with("\n#.\n.#\n\n", &legend).unwrap();
+        assert_eq!(grid.cells(), &[true, false, false, true]);
+        assert!(Grid::parse_with("#x", &legend).is_err());
+    }
+
+    #[test]
+    fn test_neighbors_stay_in_bounds() {
+        let grid = Grid::new(3, 4, 0);
+        let corner: Vec<_> = grid.neighbors4((0, 0)).collect();
+        assert_eq!(corner, vec![(1, 0), (0, 1)]);
+        assert_eq!(grid.neighbors8((1, 1)).count(), 8);
+        assert_eq!(grid.neighbors8((2, 3)).count(), 3);
+        assert_eq!(grid.get((3, 0)), None);
+    }
+}
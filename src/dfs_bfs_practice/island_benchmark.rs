@@ -0,0 +1,209 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::collections::{HashSet, VecDeque};
+use std::env;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use rust_learning::dfs_bfs_practice::bitset::{
+    bfs_distance_bits, count_islands_bits, explore_island_bits, BitGrid,
+};
+use rust_learning::rng::XorShift64;
+
+// =============================================================================
+// ⏱️ 巨大な地図でのベンチマーク: HashSet版 と ビット版
+// =============================================================================
+//
+// 使い方:
+//   cargo run --release --bin island_benchmark                        … 10000×10000（ビット版だけ）
+//   cargo run --release --bin island_benchmark -- 2000 --with-hashset … 2000×2000 で両方を比較
+//   cargo run --release --bin island_benchmark -- --with-hashset      … 10000×10000 で両方を比較
+//
+// 10000×10000 の HashSet版は、visited だけで1〜2GB以上のメモリを使います（ビット版は12.5MB）。
+// メモリが足りなくならないよう、HashSet版は --with-hashset を付けたときだけ測ります。
+// 10000×10000 での比較は、メモリに余裕のあるマシンで --with-hashset を付けて実行してください。
+//
+// 2つの版は、地図（BitGrid）も探索のコードも同じで、visited の持ち方だけが違います。
+//   島の数え上げ … どちらも explore_island_bits（dfs.rs の walk_dfs）
+//   BFS        … bfs_distance_bits と、visited を HashSet にしただけの bfs_distance_hashset
+//
+// メモリは見積もりではなく、このプログラムの全ての確保を数えるアロケータで測ります。
+// 「探索の実行中に増えたメモリの最大値」を表示します（入力の地図は含まない）。
+
+// 確保中のバイト数と、測定を始めてからの最大値を数えるアロケータ
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+fn record_alloc(size: usize) {
+    let now = ALLOCATED.fetch_add(size, Ordering::Relaxed) + size;
+    PEAK.fetch_max(now, Ordering::Relaxed);
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+            record_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+// f を実行して、結果・かかった時間・実行中に増えたメモリの最大値（バイト）を返す
+fn measure<R>(f: impl FnOnce() -> R) -> (R, Duration, usize) {
+    let before = ALLOCATED.load(Ordering::Relaxed);
+    PEAK.store(before, Ordering::Relaxed);
+    let started = Instant::now();
+    let result = f();
+    let elapsed = started.elapsed();
+    (result, elapsed, PEAK.load(Ordering::Relaxed) - before)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let size: usize = match args.iter().find(|arg| !arg.starts_with("--")) {
+        Some(arg) => match arg.parse() {
+            Ok(size) if size > 0 => size,
+            _ => {
+                eprintln!("地図の大きさは正の整数で指定してください: {}", arg);
+                return;
+            }
+        },
+        None => 10000,
+    };
+    let with_hashset = args.iter().any(|arg| arg == "--with-hashset");
+
+    println!("=== {}×{} の地図でのベンチマーク ===", size, size);
+    if !with_hashset {
+        println!("（HashSet版と比べるときは --with-hashset を付けてください）");
+    }
+
+    // 島の数え上げ: 陸地45%（島がたくさんできる）
+    println!();
+    println!("--- 島の数え上げ（陸地45%） ---");
+    let land = random_bits(size, 0.45, 1);
+    report("地図（BitGrid）", land.bytes());
+    let ((count, max_area), elapsed, peak) = measure(|| count_islands_bits(&land));
+    println!(
+        "ビット版: 島 {}個, 最大 {}マス, {:?}",
+        count, max_area, elapsed
+    );
+    report("ビット版 実行中の最大メモリ", peak);
+
+    if with_hashset {
+        let (result, elapsed, peak) = measure(|| count_islands_hashset(&land));
+        println!(
+            "HashSet版: 島 {}個, 最大 {}マス, {:?}",
+            result.0, result.1, elapsed
+        );
+        report("HashSet版 実行中の最大メモリ", peak);
+        assert_eq!(
+            result,
+            (count, max_area),
+            "ビット版とHashSet版の結果が違います"
+        );
+    }
+    drop(land);
+
+    // BFS: 通路70%の地図で左上から右下へ
+    println!();
+    println!("--- BFS（通路70%、左上 → 右下） ---");
+    let mut passable = random_bits(size, 0.7, 2);
+    let (start, goal) = ((0, 0), (size - 1, size - 1));
+    // スタートとゴールのまわりを空けて、閉じ込められないようにする
+    for d in 0..size.min(4) {
+        for e in 0..size.min(4) {
+            passable.set((d, e), true);
+            passable.set((size - 1 - d, size - 1 - e), true);
+        }
+    }
+    let (distance, elapsed, peak) = measure(|| bfs_distance_bits(&passable, start, goal));
+    println!(
+        "ビット版: 距離 {}, {:?}",
+        format_distance(distance),
+        elapsed
+    );
+    report("ビット版 実行中の最大メモリ", peak);
+
+    if with_hashset {
+        let (hashed, elapsed, peak) = measure(|| bfs_distance_hashset(&passable, start, goal));
+        println!("HashSet版: 距離 {}, {:?}", format_distance(hashed), elapsed);
+        report("HashSet版 実行中の最大メモリ", peak);
+        assert_eq!(hashed, distance, "ビット版とHashSet版の結果が違います");
+    }
+}
+
+// シード付きの乱数で、確率densityでtrueのマスを置いた地図
+fn random_bits(size: usize, density: f64, seed: u64) -> BitGrid {
+    let mut rng = XorShift64::new(seed);
+    BitGrid::from_fn(size, size, |_, _| rng.chance(density))
+}
+
+// 比較用: count_islands_bits と同じDFSで、visited だけを HashSet にした版
+fn count_islands_hashset(land: &BitGrid) -> (usize, usize) {
+    let mut visited: HashSet<(usize, usize)> = HashSet::new();
+    let (mut count, mut max_area) = (0, 0);
+    for row in 0..land.rows() {
+        for col in 0..land.cols() {
+            if !land.get((row, col)) || visited.contains(&(row, col)) {
+                continue;
+            }
+            let area = explore_island_bits(land, &mut visited, (row, col));
+            count += 1;
+            max_area = max_area.max(area);
+        }
+    }
+    (count, max_area)
+}
+
+// 比較用: bfs_distance_bits と同じBFSで、visited だけを HashSet にした版
+fn bfs_distance_hashset(
+    passable: &BitGrid,
+    start: (usize, usize),
+    goal: (usize, usize),
+) -> Option<usize> {
+    if !passable.get(start) || !passable.get(goal) {
+        return None;
+    }
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::new();
+    visited.insert(start);
+    queue.push_back((start, 0));
+    while let Some((current, distance)) = queue.pop_front() {
+        if current == goal {
+            return Some(distance);
+        }
+        for next in passable.neighbors4(current) {
+            if passable.get(next) && visited.insert(next) {
+                queue.push_back((next, distance + 1));
+            }
+        }
+    }
+    None
+}
+
+fn report(name: &str, bytes: usize) {
+    println!("  {}: {:.1} MB", name, bytes as f64 / 1_000_000.0);
+}
+
+fn format_distance(distance: Option<usize>) -> String {
+    distance.map_or("-".to_string(), |d| d.to_string())
+}
@@ -1,104 +1,121 @@
-use std::collections::{HashSet};
+use std::env;
+use std::fs::File;
+use std::io::BufReader;
+
+use rust_learning::dfs_bfs_practice::bitset::BitGrid;
+use rust_learning::dfs_bfs_practice::dfs::count_islands_dfs;
+use rust_learning::dfs_bfs_practice::grid::Grid;
+use rust_learning::dfs_bfs_practice::streaming::{stream_islands, StreamedIsland};
 
 // TODO: この関数を実装してください
 // DFS（深度優先探索）で1つの島を探索し、訪問済みにする
-fn dfs(grid: &Vec<Vec<char>>, visited: &mut HashSet<(usize, usize)>, row: usize, col: usize) {
+fn dfs(grid: &Grid<char>, visited: &mut BitGrid, row: usize, col: usize) {
     // ヒント:
     // 1. 現在地を訪問済みにする
     // 2. 4方向を調べる
     // 3. 次の条件を満たしたら再帰的にdfsを呼ぶ:
-    //    - 範囲内
+    //    - 範囲内（neighbors4が範囲外を除いてくれる）
     //    - 陸地（'1'）
     //    - 未訪問
     visited.insert((row, col));
-    // println!("inserted row:{} col:{}", row, col);
-    let directions: [(isize, isize); 4] = [(0,1), (1,0), (0,-1), (-1,0)];
-    for direction in directions {
-        let (target_row, target_col) = direction;
-        let next_row = row as isize + target_row;
-        let next_col = col as isize + target_col;
-
-        if next_row < 0 || next_col < 0 {
-            continue;
-        };
-        let new_row = next_row as usize;
-        let new_col = next_col as usize;
-        if new_row > (grid.len() - 1) || new_col > (grid[new_row].len() -1) {
-            continue;
-        }
-        let is_land = grid[new_row][new_col] == '1';
-        if is_land && !visited.contains(&(new_row, new_col)) {
-            dfs(grid,visited, new_row, new_col);
+    for (new_row, new_col) in grid.neighbors4((row, col)) {
+        let is_land = grid[(new_row, new_col)] == '1';
+        if is_land && !visited.get((new_row, new_col)) {
+            dfs(grid, visited, new_row, new_col);
         }
     }
 }
 
-// TODO: この関数を実装してください  
+// TODO: この関数を実装してください
 // グリッド全体を調べて島の数を数える
-fn count_islands(grid: &Vec<Vec<char>>) -> usize {
+fn count_islands(grid: &Grid<char>) -> usize {
     // ヒント:
     // 1. visitedを作成
     // 2. 全マスをループ
     // 3. 陸地('1')かつ未訪問なら:
     //    - dfsで島全体を探索
     //    - 島の数を+1
-    let mut visited: HashSet<(usize, usize)> = HashSet::new();
+    // visitedは1マス1ビットのBitGrid（HashSetより小さく、ハッシュ計算もいらない）
+    let mut visited = BitGrid::new(grid.rows(), grid.cols());
     let mut island_count = 0;
-    // let directions =  [(1, 0), (0, 1), (-1, 0), (0, -1)];
-    for i in 0..grid.len() {
-        for j in 0..grid[0].len() {
-            if grid[i][j] == '1' && visited.contains(&(i,j)) == false {
-                dfs(grid, &mut visited, i, j);
-                island_count = island_count + 1;
-            }
+    for (i, j) in grid.positions() {
+        if grid[(i, j)] == '1' && !visited.get((i, j)) {
+            dfs(grid, &mut visited, i, j);
+            island_count += 1;
         }
     }
-    
+
     island_count
 }
 
 fn main() {
     println!("=== DFS学習: 島の数を数える問題 ===\n");
-    
+
     // テストケース1: 2つの島
-    let grid1 = vec![
-        vec!['1', '1', '0', '0', '0'],
-        vec!['1', '1', '0', '0', '0'],
-        vec!['0', '0', '1', '0', '0'],
-        vec!['0', '0', '0', '1', '1'],
-    ];
-    
+    let grid1 = Grid::parse(
+        "\
+11000
+11000
+00100
+00011",
+    )
+    .unwrap();
+
     println!("グリッド1:");
-    for row in &grid1 {
-        println!("{}", row.iter().collect::<String>());
-    }
+    print!("{}", grid1);
 
     let result1 = count_islands(&grid1);
     println!("島の数: {}\n", result1);
 
-
-    
     // テストケース2: 3つの島
-    let grid2 = vec![
-        vec!['1', '0', '0', '1', '0'],
-        vec!['0', '0', '0', '0', '0'],
-        vec!['0', '1', '1', '0', '0'],
-        vec!['0', '1', '0', '0', '1'],
-    ];
-    
+    let grid2 = Grid::parse(
+        "\
+10010
+00000
+01100
+01001",
+    )
+    .unwrap();
+
     println!("グリッド2:");
-    for row in &grid2 {
-        println!("{}", row.iter().collect::<String>());
-    }
-    
+    print!("{}", grid2);
+
     let result2 = count_islands(&grid2);
     println!("島の数: {}\n", result2);
-    
+
+    // 1行ずつ読みながら数える（地図全体をメモリに載せない）
+    // cargo run --bin island_counter_dfs -- mask.txt でファイルも読める（'1' = 陸地）
+    println!("1行ずつ読む版（グリッド2）:");
+    let text = grid2.to_string();
+    match stream_islands(text.as_bytes(), |c| c == '1', print_streamed) {
+        Ok(summary) => println!("島の数: {}, 最大面積: {}\n", summary.count, summary.largest),
+        Err(e) => println!("エラー: {}\n", e),
+    }
+    // 大きな島: 再帰のdfsは陸地1マスごとに呼び出しが深くなり、スタックが溢れる
+    // ライブラリの count_islands_dfs は Vec を自前のスタックにした版（訪問順は再帰と同じ）
+    println!("全部陸地の2000×2000（スタック版のDFS）:");
+    let huge = Grid::new(2000, 2000, '1');
+    let (count, max_area) = count_islands_dfs(&huge, |&c| c == '1');
+    println!("島の数: {}, 最大面積: {}\n", count, max_area);
+
+    if let Some(path) = env::args().nth(1) {
+        let result = File::open(&path)
+            .map_err(|e| format!("ファイルを開けません({}): {}", path, e))
+            .and_then(|file| stream_islands(BufReader::new(file), |c| c == '1', print_streamed));
+        match result {
+            Ok(summary) => println!(
+                "{}: {}×{}, 島の数: {}, 最大面積: {}\n",
+                path, summary.rows, summary.cols, summary.count, summary.largest
+            ),
+            Err(e) => println!("エラー: {}\n", e),
+        }
+    }
+
     println!("\n問題説明:");
     println!("'1' = 陸地, '0' = 水");
     println!("隣接する陸地（上下左右）は同じ島とみなす");
     println!("斜めは隣接とみなさない");
-    
+
     println!("\n実装のヒント:");
     println!("1. count_islands関数:");
     println!("   - 全マスをチェック");
@@ -108,9 +125,19 @@ fn main() {
     println!("   - 現在地を訪問済みにする");
     println!("   - 4方向の隣接マスをチェック");
     println!("   - 陸地なら再帰的にDFS");
-    
+    println!("   - 大きな島では再帰の代わりにVecをスタックとして使う");
+
     println!("\nDFSの特徴:");
-    println!("- 再帰を使って深く探索");
+    println!("- 再帰（または自前のスタック）を使って深く探索");
     println!("- BFSと違いキューは使わない");
     println!("- 1つの島を全部訪問してから次の島へ");
-}
\ No newline at end of file
+}
+
+// 完成した島を1つ表示する
+fn print_streamed(island: StreamedIsland) {
+    let b = island.bounding_box;
+    println!(
+        "  面積 {}: ({}, {}) 〜 ({}, {})",
+        island.area, b.min_row, b.min_col, b.max_row, b.max_col
+    );
+}
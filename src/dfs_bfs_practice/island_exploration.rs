@@ -1,4 +1,20 @@
-use std::{collections::{HashSet, VecDeque}, isize};
+use std::collections::VecDeque;
+
+use rust_learning::dfs_bfs_practice::bitset::BitGrid;
+use rust_learning::dfs_bfs_practice::dfs::count_islands_on;
+use rust_learning::dfs_bfs_practice::grid::Grid;
+use rust_learning::dfs_bfs_practice::island_tracker::{
+    offline_island_counts, IslandOp, IslandTracker,
+};
+use rust_learning::dfs_bfs_practice::labeling::{label_components, Connectivity};
+use rust_learning::dfs_bfs_practice::lakes::find_lakes;
+use rust_learning::dfs_bfs_practice::multi_source_bfs::{bridge_matrix, shortest_bridge};
+use rust_learning::dfs_bfs_practice::region::{
+    dilate, erode, flood_fill, grow_region, trace_boundary,
+};
+use rust_learning::dfs_bfs_practice::topology::{
+    CornerRule, Diagonal, Hex, Orthogonal, Topology, Torus,
+};
 
 // =============================================================================
 // 🏝️ 島の探索問題: BFSとDFSの実装練習
@@ -6,7 +22,7 @@ use std::{collections::{HashSet, VecDeque}, isize};
 //
 // 海と島で構成されたマップが与えられます。
 // '.' = 海、'#' = 陸地
-// 
+//
 // 【問題】
 // 1. 島の数を数える（連結した陸地を1つの島とする）
 // 2. 最大の島の面積を求める
@@ -17,8 +33,8 @@ use std::{collections::{HashSet, VecDeque}, isize};
 
 // TODO: DFSを使って島を探索する関数
 fn dfs_explore_island(
-    grid: &Vec<Vec<char>>,
-    visited: &mut HashSet<(usize, usize)>,
+    grid: &Grid<char>,
+    visited: &mut BitGrid,
     start_row: usize,
     start_col: usize,
 ) -> usize {
@@ -28,28 +44,20 @@ fn dfs_explore_island(
     // 3. 訪問した陸地の数（面積）を返す
     // 4. visitedに訪問済みを記録（他の島と区別するため）
     visited.insert((start_row, start_col));
-    let directions: [(isize, isize); 4] = [(1,0),(0,1),(-1,0),(0,-1)];
     let mut size = 1;
-    for direction in directions {
-        let (next_row, next_col) = direction;
-        let target_row = ( start_row as isize ) + next_row;
-        let target_col = ( start_col as isize ) + next_col;
-        let is_visited = visited.contains(&(target_row as usize, target_col as usize));
-        if target_row < 0 || target_col < 0 || is_visited{
+    for (target_row, target_col) in grid.neighbors4((start_row, start_col)) {
+        if visited.get((target_row, target_col)) {
             continue;
         }
-        if (grid.len() - 1 ) < target_row as usize || (grid[target_row as usize].len() - 1) < target_col as usize {
-            continue;
+        if grid[(target_row, target_col)] == '#' {
+            size += dfs_explore_island(grid, visited, target_row, target_col);
         }
-        if grid[target_row as usize][target_col as usize]  == '#' {
-            size = size + dfs_explore_island(grid, visited, target_row as usize, target_col as usize);
-        } 
     }
-    return size
+    size
 }
 
 // TODO: DFSを使って全ての島を見つける関数
-fn count_islands_dfs(grid: &Vec<Vec<char>>) -> (usize, usize) {
+fn count_islands_dfs(grid: &Grid<char>) -> (usize, usize) {
     // ヒント:
     // 1. グリッド全体をスキャン
     // 2. 未訪問の陸地を見つけたら、dfs_explore_islandで探索
@@ -57,32 +65,22 @@ fn count_islands_dfs(grid: &Vec<Vec<char>>) -> (usize, usize) {
     // 返り値: (島の数, 最大面積)
     let mut max_size = 0;
     let mut island_count = 0;
-    let mut visited: HashSet<(usize,usize), > = HashSet::new();
-    let mut result = (0,0);
-    for i in 0..grid.len() {
-        for j in 0..grid[i].len() {
-            let is_visited = visited.contains(&(i,j));
-            let is_sea = grid[i][j] == '.';
-            if is_visited || is_sea {
-                continue;
-            } 
-            if grid[i][j] == '#' {
-                let size = dfs_explore_island(grid, &mut visited, i, j);
-                island_count = island_count + 1;
-                if size > max_size {
-                    max_size = size;
-                }
-            };
-            result = (island_count, max_size);
+    let mut visited = BitGrid::new(grid.rows(), grid.cols());
+    for (i, j) in grid.positions() {
+        if visited.get((i, j)) || grid[(i, j)] != '#' {
+            continue;
         }
-    };
-    return result;
+        let size = dfs_explore_island(grid, &mut visited, i, j);
+        island_count += 1;
+        max_size = max_size.max(size);
+    }
+    (island_count, max_size)
 }
 
 // TODO: BFSを使って島を探索する関数
 fn bfs_explore_island(
-    grid: &Vec<Vec<char>>,
-    visited: &mut HashSet<(usize, usize)>,
+    grid: &Grid<char>,
+    visited: &mut BitGrid,
     start_row: usize,
     start_col: usize,
 ) -> usize {
@@ -94,69 +92,46 @@ fn bfs_explore_island(
     let mut queue: VecDeque<(usize, usize)> = VecDeque::new();
     queue.push_back((start_row, start_col));
     visited.insert((start_row, start_col));
-    let directions:[(isize, isize);4] = [(0,1),(1,0),(0,-1),(-1,0)];
     let mut result = 1;
-    while !queue.is_empty() {
-        let (pop_row, pop_col) = queue.pop_front().unwrap();
-        for direction in directions {
-            let (next_r, next_c) = direction;
-            let target_row = pop_row as isize + next_r;
-            let target_col = pop_col as isize + next_c;
-            if target_row < 0 || target_col < 0 {
+    while let Some(current) = queue.pop_front() {
+        for target in grid.neighbors4(current) {
+            if visited.get(target) {
                 continue;
             }
 
-            if visited.contains(&(target_row as usize, target_col as usize)) {
+            if grid[target] == '.' {
                 continue;
             }
-
-            if target_row as usize > grid.len() - 1 || target_col as usize > grid[target_row as usize].len() - 1 {
-                continue;
-            }
-
-            if grid[target_row as usize][target_col as usize] == '.' {
-                continue;
-            }
-            queue.push_back((target_row as usize, target_col as usize));
-            visited.insert((target_row as usize, target_col as usize));
+            queue.push_back(target);
+            visited.insert(target);
             result += 1;
         }
     }
-    return result
+    result
 }
 
 // TODO: BFSを使って全ての島を見つける関数
-fn count_islands_bfs(grid: &Vec<Vec<char>>) -> (usize, usize) {
+fn count_islands_bfs(grid: &Grid<char>) -> (usize, usize) {
     // ヒント:
     // count_islands_dfsと同じロジックだが、
     // dfs_explore_islandの代わりにbfs_explore_islandを使う
     let mut max_size = 0;
     let mut island_count = 0;
-    let mut visited: HashSet<(usize,usize), > = HashSet::new();
-    let mut result = (0,0);
-    for i in 0..grid.len() {
-        for j in 0..grid[i].len() {
-            let is_visited = visited.contains(&(i,j));
-            let is_sea = grid[i][j] == '.';
-            if is_visited || is_sea {
-                continue;
-            } 
-            if grid[i][j] == '#' {
-                let size = bfs_explore_island(grid, &mut visited, i, j);
-                island_count = island_count + 1;
-                if size > max_size {
-                    max_size = size;
-                }
-            };
-            result = (island_count, max_size);
+    let mut visited = BitGrid::new(grid.rows(), grid.cols());
+    for (i, j) in grid.positions() {
+        if visited.get((i, j)) || grid[(i, j)] != '#' {
+            continue;
         }
-    };
-    return result;
+        let size = bfs_explore_island(grid, &mut visited, i, j);
+        island_count += 1;
+        max_size = max_size.max(size);
+    }
+    (island_count, max_size)
 }
 
 // TODO: BFSを使って2つの島の間の最短距離を求める
 fn shortest_distance_between_islands(
-    grid: &Vec<Vec<char>>,
+    grid: &Grid<char>,
     island1_start: (usize, usize),
     island2_start: (usize, usize),
 ) -> Option<usize> {
@@ -165,65 +140,94 @@ fn shortest_distance_between_islands(
     // 2. 島1の全ての陸地を開始点として、多始点BFSを実行
     // 3. 海を渡って島2の陸地に到達するまでの最短距離を求める
     // 4. 到達できない場合はNone
-    
-    // TODO: ここに実装を書く
-    None
-}
-
-// ヘルパー関数: 4方向の隣接マスを取得
-fn get_neighbors(row: usize, col: usize, rows: usize, cols: usize) -> Vec<(usize, usize)> {
-    let mut neighbors = Vec::new();
-    let directions = [(0, 1), (1, 0), (0, -1), (-1, 0)];
-    
-    for (dr, dc) in directions {
-        let new_row = row as isize + dr;
-        let new_col = col as isize + dc;
-        
-        if new_row >= 0 && new_row < rows as isize && 
-           new_col >= 0 && new_col < cols as isize {
-            neighbors.push((new_row as usize, new_col as usize));
-        }
-    }
-    
-    neighbors
+    // 実装は multi_source_bfs::shortest_bridge（橋のマスも求められる）
+    shortest_bridge(grid, island1_start, island2_start, |&c| c == '#').map(|bridge| bridge.length)
 }
 
 fn main() {
     println!("=== 🏝️ 島の探索問題: DFSとBFS両方で解いてみよう！ ===\n");
-    
+
     // テストマップ
-    let grid = vec![
-        vec!['#', '#', '.', '.', '.', '.', '#', '#', '#'],
-        vec!['#', '.', '.', '.', '.', '.', '#', '.', '#'],
-        vec!['.', '.', '.', '#', '#', '.', '#', '#', '#'],
-        vec!['.', '.', '.', '#', '#', '.', '.', '.', '.'],
-        vec!['.', '#', '.', '.', '.', '.', '.', '.', '.'],
-        vec!['.', '#', '#', '.', '.', '#', '#', '#', '.'],
-        vec!['.', '.', '.', '.', '.', '#', '.', '#', '.'],
-        vec!['#', '#', '#', '.', '.', '#', '#', '#', '.'],
-        vec!['#', '.', '#', '.', '.', '.', '.', '.', '.'],
-    ];
-    
+    let grid = Grid::parse(
+        "\
+##....###
+#.....#.#
+...##.###
+...##....
+.#.......
+.##..###.
+.....#.#.
+###..###.
+#.#......",
+    )
+    .unwrap();
+
     println!("マップ（'#' = 陸地, '.' = 海）:");
-    for row in &grid {
-        println!("{}", row.iter().collect::<String>());
-    }
+    print!("{}", grid);
     println!();
-    
+
     // DFSでの解法
     println!("=== DFSでの解法 ===");
     let (islands_dfs, max_area_dfs) = count_islands_dfs(&grid);
     println!("島の数: {}", islands_dfs);
     println!("最大の島の面積: {}", max_area_dfs);
     println!();
-    
+
     // BFSでの解法
     println!("=== BFSでの解法 ===");
     let (islands_bfs, max_area_bfs) = count_islands_bfs(&grid);
     println!("島の数: {}", islands_bfs);
     println!("最大の島の面積: {}", max_area_bfs);
     println!();
-    
+
+    // ラベリングで島ごとの統計を求める
+    println!("=== ラベリング: 島ごとの統計 ===");
+    let labeling = label_components(&grid, |&c| c == '#', Connectivity::Four);
+    print!("{}", labeling.render());
+    for island in &labeling.islands {
+        let bb = island.bounding_box;
+        println!(
+            "島{}: 面積 {}, 周囲長 {}, 範囲 ({}, {})-({}, {}), 重心 ({:.2}, {:.2}){}",
+            island.label,
+            island.area,
+            island.perimeter,
+            bb.min_row,
+            bb.min_col,
+            bb.max_row,
+            bb.max_col,
+            island.centroid.0,
+            island.centroid.1,
+            if island.touches_border {
+                ", 端に接する"
+            } else {
+                ""
+            }
+        );
+    }
+    let eight = label_components(&grid, |&c| c == '#', Connectivity::Eight);
+    println!(
+        "斜めもつながりとみなすと: 島の数 {}, 最大面積 {}",
+        eight.count(),
+        eight.max_area()
+    );
+    println!();
+
+    // 島に囲まれた湖（穴）を見つける
+    println!("=== 湖の検出 ===");
+    let lakes = find_lakes(&grid, |&c| c == '#', Connectivity::Four);
+    for island in 0..lakes.land.count() {
+        if lakes.hole_count(island) > 0 {
+            println!("島{}: 湖 {}個", island, lakes.hole_count(island));
+        }
+    }
+    let mut lake_map = grid.clone();
+    for cell in lakes.lake_cells() {
+        lake_map[cell] = '~';
+    }
+    println!("湖のマス（'~'）:");
+    print!("{}", lake_map);
+    println!();
+
     // 島間の最短距離（BFS）
     println!("=== 島間の最短距離 ===");
     // 左上の島(0,0)と中央の島(3,3)の間の距離
@@ -231,7 +235,123 @@ fn main() {
         Some(distance) => println!("島(0,0)と島(3,3)の間の最短距離: {}", distance),
         None => println!("島(0,0)と島(3,3)は接続できません"),
     }
-    
+    if let Some(bridge) = shortest_bridge(&grid, (0, 0), (3, 3), |&c| c == '#') {
+        let mut map = grid.clone();
+        for &cell in &bridge.cells {
+            map[cell] = '=';
+        }
+        println!("橋のマス（'='）:");
+        print!("{}", map);
+    }
+    println!();
+
+    // 全ての島の組み合わせの橋の長さ
+    println!("=== 島どうしの最短の橋（長さの表） ===");
+    let matrix = bridge_matrix(&grid, |&c| c == '#');
+    for (i, row) in matrix.lengths.iter().enumerate() {
+        let cells: Vec<String> = row
+            .iter()
+            .map(|length| length.map_or("-".to_string(), |l| l.to_string()))
+            .collect();
+        let (r, c) = matrix.islands[i][0];
+        println!("島{}({}, {}): {}", i, r, c, cells.join(" "));
+    }
+
+    println!();
+
+    // 陸地を1マスずつ追加・削除しながら島を数える（Union-Find）
+    println!("=== オンラインの島カウント ===");
+    let ops = [
+        IslandOp::AddLand(0, 0),
+        IslandOp::AddLand(0, 2),
+        IslandOp::AddLand(0, 1),
+        IslandOp::AddLand(2, 2),
+        IslandOp::AddLand(1, 2),
+        IslandOp::AddWater(0, 1),
+    ];
+    let mut tracker = IslandTracker::new(3, 3);
+    let offline = offline_island_counts(3, 3, &ops).expect("操作が地図の外です");
+    for (op, expected) in ops.iter().zip(&offline) {
+        let snapshot = tracker.apply(*op).expect("操作が地図の外です");
+        println!(
+            "{:?}: 島の数 {}, 最大面積 {}",
+            op, snapshot.count, snapshot.largest
+        );
+        assert_eq!(snapshot, *expected);
+    }
+    println!(
+        "※ AddWater は毎回地図全体からUnion-Findを作り直すので、1回で 行数×列数 の時間がかかります"
+    );
+    println!("   削除が多いときは、操作列をまとめて offline_island_counts で処理します");
+
+    // 同じDFSのまま、隣のマスの決め方だけを変える
+    println!();
+    println!("=== つながり方を変えて島を数える ===");
+    let diagonal_map = Grid::parse(
+        "\
+#...#.
+.#.#..
+..#...
+.#.#.#
+#....#",
+    )
+    .expect("地図の形式が不正です");
+    print!("{}", diagonal_map);
+    let topologies: [(&str, &dyn Topology); 6] = [
+        ("4近傍", &Orthogonal),
+        ("8近傍（角抜けあり）", &Diagonal(CornerRule::Allow)),
+        ("8近傍（角抜けなし）", &Diagonal(CornerRule::NoCutting)),
+        ("六角形（奇数行ずらし）", &Hex::OddRow),
+        ("六角形（アキシャル）", &Hex::Axial),
+        ("トーラス（端がつながる）", &Torus { diagonal: false }),
+    ];
+    for (name, topology) in topologies {
+        let (count, max_area) = count_islands_on(&diagonal_map, topology, |&c| c == '#');
+        println!("{}: 島の数 {}, 最大面積 {}", name, count, max_area);
+    }
+
+    // 探索の結果で地図を書き換える
+    println!();
+    println!("=== 地図の書き換え: 塗りつぶし・輪郭・膨張と収縮 ===");
+    let mut painted = grid.clone();
+    let filled = flood_fill(&mut painted, &Orthogonal, (3, 0), '~');
+    println!(
+        "(3, 0) の海を '~' で塗りつぶし（{}マス、湖には届かない）:",
+        filled
+    );
+    print!("{}", painted);
+
+    let outline = trace_boundary(&grid, (5, 5), |&c| c == '#');
+    println!(
+        "(5, 5) の島の輪郭（時計回り、{}マス）: {:?}",
+        outline.len(),
+        outline
+    );
+
+    let land = grid.map(|&c| c == '#');
+    let show = |land: &Grid<bool>| land.map(|&l| if l { '#' } else { '.' });
+    let grown = dilate(&land, 1);
+    println!("1マス膨張:");
+    print!("{}", show(&grown));
+    // 細い島は収縮で消えるので、膨張してから収縮して近くの島をくっつける
+    println!("1マス膨張してから1マス収縮:");
+    print!("{}", show(&erode(&grown, 1)));
+
+    // 中央が盛り上がった高さの地図
+    let heights: Grid<i32> = Grid::from_fn(7, 11, |row, col| {
+        let (dr, dc) = (row as i32 - 3, col as i32 - 5);
+        20 - dr * dr - dc * dc
+    });
+    let region = grow_region(&heights, (3, 5), 6.0);
+    println!(
+        "高さの地図で (3, 5) との差が6以内の領域（{}マス）:",
+        region.len()
+    );
+    let mut marks = heights.map(|_| '.');
+    for &cell in &region {
+        marks[cell] = '#';
+    }
+    print!("{}", marks);
     println!("\n実装のヒント:");
     println!("1. DFS実装:");
     println!("   - 再帰またはスタックを使用");
@@ -249,10 +369,10 @@ fn main() {
     println!("   - 最初に島2に到達した時点が最短");
     println!();
     println!("両方の手法で同じ結果が得られることを確認してください！");
-    
-    // デバッグ用: get_neighbors関数のテスト
+
+    // デバッグ用: neighbors4のテスト
     println!("\nデバッグ: (1,1)の隣接マス:");
-    for (r, c) in get_neighbors(1, 1, 9, 9) {
+    for (r, c) in grid.neighbors4((1, 1)) {
         println!("  ({}, {})", r, c);
     }
-}
\ No newline at end of file
+}
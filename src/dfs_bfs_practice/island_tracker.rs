@@ -0,0 +1,507 @@
+use std::collections::BTreeMap;
+
+use super::grid::Grid;
+use crate::union_find::{RollbackUnionFind, UnionFind};
+
+// =============================================================================
+// 🧩 オンラインの島カウント（Union-Find）
+// =============================================================================
+//
+// 陸地が1マスずつ増えていくとき、毎回 count_islands で地図全体を数え直すと
+// 操作の数 × マスの数 の時間がかかります。
+//
+// Union-Findを使うと、陸地を1マス足すたびに
+//   1. 島の数を +1 する（新しい1マスの島）
+//   2. 上下左右の陸地と併合し、別の島だった分だけ -1 する
+// だけで済みます。
+//
+// ただしUnion-Findは「分ける」ことができません。陸地が海に戻る操作は
+//   - IslandTracker       … 海に戻したら作り直す（オンラインで簡単な方法。
+//                           1回ごとに 行数 × 列数 の時間がかかる）
+//   - offline_island_counts … 操作列を先に全部受け取り、各マスが陸地である
+//                             期間をセグメント木に載せて、取り消し可能な
+//                             Union-Findでたどる（オフラインで速い方法）
+// の2通りで扱います。
+
+// 1回の操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IslandOp {
+    AddLand(usize, usize),
+    AddWater(usize, usize),
+}
+
+impl IslandOp {
+    pub fn position(&self) -> (usize, usize) {
+        match *self {
+            IslandOp::AddLand(row, col) | IslandOp::AddWater(row, col) => (row, col),
+        }
+    }
+}
+
+// 操作後の状態: 島の数と最大の島の面積
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IslandSnapshot {
+    pub count: usize,
+    pub largest: usize,
+}
+
+// 島の面積の多重集合（最大の面積をすぐ取り出すため）
+#[derive(Debug, Clone, Default)]
+struct AreaSet {
+    // 面積 → その面積の島の数
+    areas: BTreeMap<usize, usize>,
+}
+
+impl AreaSet {
+    fn insert(&mut self, area: usize) {
+        *self.areas.entry(area).or_insert(0) += 1;
+    }
+
+    fn remove(&mut self, area: usize) {
+        if let Some(n) = self.areas.get_mut(&area) {
+            *n -= 1;
+            if *n == 0 {
+                self.areas.remove(&area);
+            }
+        }
+    }
+
+    fn largest(&self) -> usize {
+        self.areas.keys().next_back().copied().unwrap_or(0)
+    }
+}
+
+// オンラインの島カウンタ
+#[derive(Debug, Clone)]
+pub struct IslandTracker {
+    land: Grid<bool>,
+    uf: UnionFind,
+    count: usize,
+    areas: AreaSet,
+}
+
+impl IslandTracker {
+    // 全て海の地図から始める
+    pub fn new(rows: usize, cols: usize) -> IslandTracker {
+        IslandTracker {
+            land: Grid::new(rows, cols, false),
+            uf: UnionFind::new(rows * cols),
+            count: 0,
+            areas: AreaSet::default(),
+        }
+    }
+
+    pub fn snapshot(&self) -> IslandSnapshot {
+        IslandSnapshot {
+            count: self.count,
+            largest: self.areas.largest(),
+        }
+    }
+
+    pub fn is_land(&self, pos: (usize, usize)) -> bool {
+        self.land.get(pos).copied().unwrap_or(false)
+    }
+
+    // (row, col) を陸地にする（既に陸地なら何もしない）
+    pub fn add_land(&mut self, row: usize, col: usize) -> Result<IslandSnapshot, String> {
+        self.check_bounds(row, col)?;
+        if !self.land[(row, col)] {
+            self.land[(row, col)] = true;
+            self.connect((row, col));
+        }
+        Ok(self.snapshot())
+    }
+
+    // (row, col) を海に戻す（既に海なら何もしない）
+    // Union-Findは分割できないので、残った陸地からUnion-Findを作り直す
+    // そのため1回ごとに O(行数 × 列数) かかる（add_land のほぼ O(1) とは違う）
+    // 削除が多いなら、操作列をまとめて offline_island_counts に渡す方が速い
+    pub fn add_water(&mut self, row: usize, col: usize) -> Result<IslandSnapshot, String> {
+        self.check_bounds(row, col)?;
+        if self.land[(row, col)] {
+            self.land[(row, col)] = false;
+            self.rebuild();
+        }
+        Ok(self.snapshot())
+    }
+
+    pub fn apply(&mut self, op: IslandOp) -> Result<IslandSnapshot, String> {
+        match op {
+            IslandOp::AddLand(row, col) => self.add_land(row, col),
+            IslandOp::AddWater(row, col) => self.add_water(row, col),
+        }
+    }
+
+    fn check_bounds(&self, row: usize, col: usize) -> Result<(), String> {
+        if row >= self.land.rows() || col >= self.land.cols() {
+            return Err(format!(
+                "({}, {}) は地図の外です（{}行{}列）",
+                row,
+                col,
+                self.land.rows(),
+                self.land.cols()
+            ));
+        }
+        Ok(())
+    }
+
+    // 新しい陸地posを1マスの島として数え、隣の陸地と併合する
+    fn connect(&mut self, pos: (usize, usize)) {
+        self.count += 1;
+        self.areas.insert(1);
+        let id = self.land.index_of(pos);
+        for next in self.land.neighbors4(pos) {
+            if !self.land[next] {
+                continue;
+            }
+            let next_id = self.land.index_of(next);
+            let (a, b) = (self.uf.size(id), self.uf.size(next_id));
+            if self.uf.union(id, next_id) {
+                self.count -= 1;
+                self.areas.remove(a);
+                self.areas.remove(b);
+                self.areas.insert(a + b);
+            }
+        }
+    }
+
+    fn rebuild(&mut self) {
+        self.uf = UnionFind::new(self.land.rows() * self.land.cols());
+        self.count = 0;
+        self.areas = AreaSet::default();
+        // 残っている陸地を全て海にしてから、1マスずつ足し直す
+        let cells = self.land.find_all(|&land| land);
+        self.land = Grid::new(self.land.rows(), self.land.cols(), false);
+        for pos in cells {
+            self.land[pos] = true;
+            self.connect(pos);
+        }
+    }
+}
+
+// =============================================================================
+// 🕰️ オフライン版: 削除も含む操作列をまとめて処理する
+// =============================================================================
+//
+// 各マスが陸地である期間 [陸地になった操作, 海に戻った操作) を求めると、
+// 「ある期間だけ存在するマス」と「ある期間だけ存在する隣接（両方が陸地の期間）」
+// の集まりになります。
+//
+// 時刻（操作の番号）のセグメント木の各ノードに、そのノードの区間ずっと
+// 存在するマスと隣接を載せ、根から葉へたどりながら
+//   ノードに入る → マスを追加して隣接を併合
+//   ノードから出る → 併合を取り消す（RollbackUnionFind）
+// とすると、葉（各時刻）に着いた時点の島の数と最大面積がその時刻の答えです。
+// 全体で O((操作数) log(操作数) log(マスの数)) になります。
+
+// セグメント木のノードに載せる作業
+#[derive(Debug, Default, Clone)]
+struct NodeTasks {
+    // このノードの期間ずっと陸地のマス
+    cells: Vec<usize>,
+    // このノードの期間ずっと両方が陸地の隣接マスの組
+    edges: Vec<(usize, usize)>,
+}
+
+// 取り消すための変更の記録
+#[derive(Debug, Clone, Copy)]
+enum Change {
+    Activate,
+    Merge(usize, usize),
+}
+
+struct OfflineState {
+    uf: RollbackUnionFind,
+    count: usize,
+    areas: AreaSet,
+    changes: Vec<Change>,
+}
+
+impl OfflineState {
+    fn activate(&mut self) {
+        self.count += 1;
+        self.areas.insert(1);
+        self.changes.push(Change::Activate);
+    }
+
+    fn merge(&mut self, a: usize, b: usize) {
+        if let Some((size_a, size_b)) = self.uf.union(a, b) {
+            self.count -= 1;
+            self.areas.remove(size_a);
+            self.areas.remove(size_b);
+            self.areas.insert(size_a + size_b);
+            self.changes.push(Change::Merge(size_a, size_b));
+        }
+    }
+
+    // 変更の記録を mark の位置まで巻き戻す（Union-Findは別に巻き戻す）
+    fn undo(&mut self, mark: usize) {
+        while self.changes.len() > mark {
+            match self.changes.pop() {
+                Some(Change::Activate) => {
+                    self.count -= 1;
+                    self.areas.remove(1);
+                }
+                Some(Change::Merge(size_a, size_b)) => {
+                    self.count += 1;
+                    self.areas.remove(size_a + size_b);
+                    self.areas.insert(size_a);
+                    self.areas.insert(size_b);
+                }
+                None => break,
+            }
+        }
+    }
+}
+
+// 操作列を全て処理し、各操作の後の状態を返す
+pub fn offline_island_counts(
+    rows: usize,
+    cols: usize,
+    ops: &[IslandOp],
+) -> Result<Vec<IslandSnapshot>, String> {
+    for (i, op) in ops.iter().enumerate() {
+        let (row, col) = op.position();
+        if row >= rows || col >= cols {
+            return Err(format!(
+                "{}番目の操作: ({}, {}) は地図の外です（{}行{}列）",
+                i + 1,
+                row,
+                col,
+                rows,
+                cols
+            ));
+        }
+    }
+    if ops.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // ステップ1: 各マスが陸地である期間を求める
+    let time = ops.len();
+    let mut since: Grid<Option<usize>> = Grid::new(rows, cols, None);
+    let mut periods: Grid<Vec<(usize, usize)>> = Grid::new(rows, cols, Vec::new());
+    for (t, op) in ops.iter().enumerate() {
+        match *op {
+            IslandOp::AddLand(row, col) => {
+                if since[(row, col)].is_none() {
+                    since[(row, col)] = Some(t);
+                }
+            }
+            IslandOp::AddWater(row, col) => {
+                if let Some(start) = since[(row, col)].take() {
+                    periods[(row, col)].push((start, t));
+                }
+            }
+        }
+    }
+    for pos in since.positions() {
+        if let Some(start) = since[pos] {
+            periods[pos].push((start, time));
+        }
+    }
+
+    // ステップ2: マスと隣接をセグメント木に載せる
+    let mut tree = vec![NodeTasks::default(); 4 * time];
+    for pos in periods.positions() {
+        let id = periods.index_of(pos);
+        for &(start, end) in &periods[pos] {
+            insert(&mut tree, 1, 0, time, start, end, &mut |tasks| {
+                tasks.cells.push(id)
+            });
+        }
+        // 右と下の隣だけ見れば、全ての隣接の組を1回ずつ数えられる
+        let (row, col) = pos;
+        for next in [(row, col + 1), (row + 1, col)] {
+            if next.0 >= rows || next.1 >= cols {
+                continue;
+            }
+            let next_id = periods.index_of(next);
+            for (start, end) in overlaps(&periods[pos], &periods[next]) {
+                insert(&mut tree, 1, 0, time, start, end, &mut |tasks| {
+                    tasks.edges.push((id, next_id))
+                });
+            }
+        }
+    }
+
+    // ステップ3: 根から葉へたどって、各時刻の答えを求める
+    let mut state = OfflineState {
+        uf: RollbackUnionFind::new(rows * cols),
+        count: 0,
+        areas: AreaSet::default(),
+        changes: Vec::new(),
+    };
+    let mut answers = vec![
+        IslandSnapshot {
+            count: 0,
+            largest: 0
+        };
+        time
+    ];
+    walk(&tree, 1, 0, time, &mut state, &mut answers);
+    Ok(answers)
+}
+
+// 区間 [start, end) を覆うノードに作業を追加する（ノードnodeの区間は [lo, hi)）
+fn insert<F>(
+    tree: &mut [NodeTasks],
+    node: usize,
+    lo: usize,
+    hi: usize,
+    start: usize,
+    end: usize,
+    add: &mut F,
+) where
+    F: FnMut(&mut NodeTasks),
+{
+    if end <= lo || hi <= start {
+        return;
+    }
+    if start <= lo && hi <= end {
+        add(&mut tree[node]);
+        return;
+    }
+    let mid = (lo + hi) / 2;
+    insert(tree, node * 2, lo, mid, start, end, add);
+    insert(tree, node * 2 + 1, mid, hi, start, end, add);
+}
+
+// 2つのマスの陸地の期間の重なり（どちらも時刻順に並んでいる）
+fn overlaps(a: &[(usize, usize)], b: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let mut result = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        let start = a[i].0.max(b[j].0);
+        let end = a[i].1.min(b[j].1);
+        if start < end {
+            result.push((start, end));
+        }
+        if a[i].1 < b[j].1 {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    result
+}
+
+// セグメント木をたどる
+// 隣接の期間は両端のマスの期間に含まれるので、同じノードか祖先で
+// マスが先に追加されている（ノード内でもマスを先に追加する）
+fn walk(
+    tree: &[NodeTasks],
+    node: usize,
+    lo: usize,
+    hi: usize,
+    state: &mut OfflineState,
+    answers: &mut [IslandSnapshot],
+) {
+    let uf_mark = state.uf.snapshot();
+    let change_mark = state.changes.len();
+
+    for _ in &tree[node].cells {
+        state.activate();
+    }
+    for &(a, b) in &tree[node].edges {
+        state.merge(a, b);
+    }
+
+    if hi - lo == 1 {
+        answers[lo] = IslandSnapshot {
+            count: state.count,
+            largest: state.areas.largest(),
+        };
+    } else {
+        let mid = (lo + hi) / 2;
+        walk(tree, node * 2, lo, mid, state, answers);
+        walk(tree, node * 2 + 1, mid, hi, state, answers);
+    }
+
+    state.uf.rollback(uf_mark);
+    state.undo(change_mark);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dfs_bfs_practice::labeling::{label_components, Connectivity};
+    use crate::rng::XorShift64;
+
+    #[test]
+    fn test_add_land_merges_islands() {
+        let mut tracker = IslandTracker::new(3, 3);
+        assert_eq!(
+            tracker.add_land(0, 0).unwrap(),
+            IslandSnapshot {
+                count: 1,
+                largest: 1
+            }
+        );
+        assert_eq!(
+            tracker.add_land(0, 2).unwrap(),
+            IslandSnapshot {
+                count: 2,
+                largest: 1
+            }
+        );
+        assert_eq!(
+            tracker.add_land(0, 1).unwrap(),
+            IslandSnapshot {
+                count: 1,
+                largest: 3
+            }
+        );
+        // 同じマスをもう一度陸地にしても変わらない
+        assert_eq!(
+            tracker.add_land(0, 1).unwrap(),
+            IslandSnapshot {
+                count: 1,
+                largest: 3
+            }
+        );
+        // 真ん中を海に戻すと2つに分かれる
+        assert_eq!(
+            tracker.add_water(0, 1).unwrap(),
+            IslandSnapshot {
+                count: 2,
+                largest: 1
+            }
+        );
+        assert!(tracker.add_land(3, 0).is_err());
+        assert!(offline_island_counts(3, 3, &[IslandOp::AddWater(0, 5)]).is_err());
+    }
+
+    #[test]
+    fn test_online_and_offline_match_labeling() {
+        let mut rng = XorShift64::new(35);
+        for _ in 0..20 {
+            let (rows, cols) = (1 + rng.below(6), 1 + rng.below(6));
+            let ops: Vec<IslandOp> = (0..60)
+                .map(|_| {
+                    let (row, col) = (rng.below(rows), rng.below(cols));
+                    if rng.chance(0.7) {
+                        IslandOp::AddLand(row, col)
+                    } else {
+                        IslandOp::AddWater(row, col)
+                    }
+                })
+                .collect();
+
+            let offline = offline_island_counts(rows, cols, &ops).unwrap();
+            let mut tracker = IslandTracker::new(rows, cols);
+            let mut land = Grid::new(rows, cols, false);
+            for (t, &op) in ops.iter().enumerate() {
+                let online = tracker.apply(op).unwrap();
+                land[op.position()] = matches!(op, IslandOp::AddLand(..));
+                let labeling = label_components(&land, |&l| l, Connectivity::Four);
+                let expected = IslandSnapshot {
+                    count: labeling.count(),
+                    largest: labeling.max_area(),
+                };
+                assert_eq!(online, expected, "操作{}: {:?}", t, op);
+                assert_eq!(offline[t], expected, "操作{}: {:?}", t, op);
+            }
+        }
+    }
+}
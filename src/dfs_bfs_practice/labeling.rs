@@ -0,0 +1,247 @@
+use std::collections::VecDeque;
+
+use super::grid::{Grid, DIRECTIONS4, DIRECTIONS8};
+
+// =============================================================================
+// 🏷️ 連結成分のラベリング: 島ごとの統計
+// =============================================================================
+//
+// count_islands_dfs / count_islands_bfs は (島の数, 最大面積) しか返しません。
+// ここでは各マスに「何番目の島か」のラベルを付け、島ごとに
+//   面積, 周囲長, 外接矩形, 重心, マスの一覧, 地図の端に接しているか
+// をまとめて求めます。
+//
+// 斜めでつながった陸地を同じ島とみなすか（8近傍）、みなさないか（4近傍）を選べます。
+//
+// 例（4近傍）:      ラベル:
+//   ##..#           00..1
+//   #...#           0...1
+//   ..#..           ..2..
+
+// 陸地のつながり方
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    // 上下左右だけ
+    Four,
+    // 斜めも含む
+    Eight,
+}
+
+impl Connectivity {
+    pub fn directions(self) -> &'static [(isize, isize)] {
+        match self {
+            Connectivity::Four => &DIRECTIONS4,
+            Connectivity::Eight => &DIRECTIONS8,
+        }
+    }
+}
+
+// 外接矩形（両端を含む）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoundingBox {
+    pub min_row: usize,
+    pub min_col: usize,
+    pub max_row: usize,
+    pub max_col: usize,
+}
+
+impl BoundingBox {
+    pub fn height(&self) -> usize {
+        self.max_row - self.min_row + 1
+    }
+
+    pub fn width(&self) -> usize {
+        self.max_col - self.min_col + 1
+    }
+}
+
+// 1つの島の統計
+#[derive(Debug, Clone, PartialEq)]
+pub struct IslandStats {
+    // ラベル番号（左上から見つけた順に0, 1, 2, ...）
+    pub label: usize,
+    // 面積（マスの数）
+    pub area: usize,
+    // 周囲長（陸地のマスの辺のうち、海か地図の外に面している辺の数）
+    pub perimeter: usize,
+    pub bounding_box: BoundingBox,
+    // 重心 (行, 列) の平均
+    pub centroid: (f64, f64),
+    // 島のマス（見つけた順）
+    pub cells: Vec<(usize, usize)>,
+    // 地図の端に接しているか
+    pub touches_border: bool,
+}
+
+// ラベリングの結果
+#[derive(Debug, Clone, PartialEq)]
+pub struct Labeling {
+    // 各マスのラベル（海はNone）
+    pub labels: Grid<Option<usize>>,
+    // islands[label] = その島の統計
+    pub islands: Vec<IslandStats>,
+}
+
+impl Labeling {
+    pub fn count(&self) -> usize {
+        self.islands.len()
+    }
+
+    pub fn max_area(&self) -> usize {
+        self.islands
+            .iter()
+            .map(|island| island.area)
+            .max()
+            .unwrap_or(0)
+    }
+
+    // ラベルを文字で表した地図（0-9, a-z, A-Z の順、それ以上は '*'、海は '.'）
+    pub fn render(&self) -> Grid<char> {
+        const SYMBOLS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        self.labels.map(|label| match label {
+            Some(l) if *l < SYMBOLS.len() => SYMBOLS[*l] as char,
+            Some(_) => '*',
+            None => '.',
+        })
+    }
+}
+
+// 陸地の連結成分にラベルを付け、島ごとの統計を求める
+// BFSで1つずつ島を塗っていく（再帰しないので大きな島でもスタックを使い切らない）
+pub fn label_components<T, F>(grid: &Grid<T>, is_land: F, connectivity: Connectivity) -> Labeling
+where
+    F: Fn(&T) -> bool,
+{
+    let mut labels: Grid<Option<usize>> = Grid::new(grid.rows(), grid.cols(), None);
+    let mut islands = Vec::new();
+    let mut queue = VecDeque::new();
+
+    for start in grid.positions() {
+        if labels[start].is_some() || !is_land(&grid[start]) {
+            continue;
+        }
+
+        let label = islands.len();
+        labels[start] = Some(label);
+        queue.push_back(start);
+        let mut cells = Vec::new();
+
+        while let Some(current) = queue.pop_front() {
+            cells.push(current);
+            for next in grid.neighbors_in(current, connectivity.directions()) {
+                if labels[next].is_none() && is_land(&grid[next]) {
+                    labels[next] = Some(label);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        islands.push(island_stats(grid, &is_land, label, cells));
+    }
+
+    Labeling { labels, islands }
+}
+
+// 島のマス一覧から統計を計算する
+fn island_stats<T, F>(
+    grid: &Grid<T>,
+    is_land: &F,
+    label: usize,
+    cells: Vec<(usize, usize)>,
+) -> IslandStats
+where
+    F: Fn(&T) -> bool,
+{
+    let (first_row, first_col) = cells[0];
+    let mut bounding_box = BoundingBox {
+        min_row: first_row,
+        min_col: first_col,
+        max_row: first_row,
+        max_col: first_col,
+    };
+    let mut perimeter = 0;
+    let (mut row_sum, mut col_sum) = (0usize, 0usize);
+
+    for &(row, col) in &cells {
+        bounding_box.min_row = bounding_box.min_row.min(row);
+        bounding_box.min_col = bounding_box.min_col.min(col);
+        bounding_box.max_row = bounding_box.max_row.max(row);
+        bounding_box.max_col = bounding_box.max_col.max(col);
+        row_sum += row;
+        col_sum += col;
+
+        // 4辺のうち、陸地と接していない辺を数える（地図の外も海とみなす）
+        let land_sides = grid
+            .neighbors4((row, col))
+            .filter(|&next| is_land(&grid[next]))
+            .count();
+        perimeter += 4 - land_sides;
+    }
+
+    let area = cells.len();
+    let touches_border = bounding_box.min_row == 0
+        || bounding_box.min_col == 0
+        || bounding_box.max_row + 1 == grid.rows()
+        || bounding_box.max_col + 1 == grid.cols();
+
+    IslandStats {
+        label,
+        area,
+        perimeter,
+        bounding_box,
+        centroid: (row_sum as f64 / area as f64, col_sum as f64 / area as f64),
+        cells,
+        touches_border,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_four_and_eight_connectivity() {
+        let grid = Grid::parse("#.#\n.#.\n#.#").unwrap();
+        let four = label_components(&grid, |&c| c == '#', Connectivity::Four);
+        let eight = label_components(&grid, |&c| c == '#', Connectivity::Eight);
+        assert_eq!(four.count(), 5);
+        assert_eq!(eight.count(), 1);
+        assert_eq!(eight.max_area(), 5);
+        assert!(eight.islands[0].touches_border);
+        assert_eq!(eight.islands[0].centroid, (1.0, 1.0));
+    }
+
+    #[test]
+    fn test_island_stats() {
+        let grid = Grid::parse(
+            "\
+.....
+.##..
+.###.
+.....",
+        )
+        .unwrap();
+        let labeling = label_components(&grid, |&c| c == '#', Connectivity::Four);
+        let island = &labeling.islands[0];
+        assert_eq!(island.area, 5);
+        assert_eq!(island.perimeter, 10);
+        assert_eq!(
+            island.bounding_box,
+            BoundingBox {
+                min_row: 1,
+                min_col: 1,
+                max_row: 2,
+                max_col: 3
+            }
+        );
+        assert_eq!(
+            (island.bounding_box.height(), island.bounding_box.width()),
+            (2, 3)
+        );
+        assert!(!island.touches_border);
+        assert_eq!(
+            labeling.render().to_string(),
+            ".....\n.00..\n.000.\n.....\n"
+        );
+    }
+}
@@ -0,0 +1,235 @@
+use std::collections::VecDeque;
+
+use super::grid::Grid;
+use super::labeling::{label_components, Connectivity, Labeling};
+
+// =============================================================================
+// 🏞️ 湖（島の中の穴）の検出
+// =============================================================================
+//
+// これまでの島の問題では '.' の海を全部同じものとして扱っていました。
+// ここでは海を次の2つに分けます。
+//   - 外洋: 地図の外（端）とつながっている海
+//   - 湖  : 島に囲まれていて外洋に出られない海（島の「穴」）
+//
+// 手順:
+// 1. 陸地と海をそれぞれラベリングする
+//    陸地を4近傍でつなぐなら海は8近傍でつなぐ（逆も同様）。
+//    こうしないと、斜めの隙間で「囲まれているのに外とつながっている」海ができてしまう。
+// 2. 地図の外側から領域をたどる（境界からのフラッドフィル）
+//    外 → 外洋と端の島 → その島にだけ接する湖 → 湖の中の島 → ...
+//    湖を最初に見つけた島が、その湖を囲んでいる島になる。
+//
+//   ######.       島0の中に湖が1つ（中に島1がある）
+//   #....#.
+//   #.##.#.       外洋: 右端の列
+//   #....#.
+//   ######.
+
+// 湖の解析結果
+#[derive(Debug, Clone, PartialEq)]
+pub struct LakeAnalysis {
+    // 陸地のラベリング（島ごとの統計つき）
+    pub land: Labeling,
+    // 海のラベリング
+    pub water: Labeling,
+    // water_enclosed_by[w] = 海wを囲んでいる島（外洋ならNone）
+    pub water_enclosed_by: Vec<Option<usize>>,
+    // holes[i] = 島iが囲んでいる湖（海のラベル）
+    pub holes: Vec<Vec<usize>>,
+}
+
+impl LakeAnalysis {
+    // 島iの中にある湖の数
+    pub fn hole_count(&self, island: usize) -> usize {
+        self.holes[island].len()
+    }
+
+    // 海wが湖（外洋につながっていない）か
+    pub fn is_lake(&self, water: usize) -> bool {
+        self.water_enclosed_by[water].is_some()
+    }
+
+    // 全ての湖のマス
+    pub fn lake_cells(&self) -> Vec<(usize, usize)> {
+        (0..self.water.count())
+            .filter(|&w| self.is_lake(w))
+            .flat_map(|w| self.water.islands[w].cells.iter().copied())
+            .collect()
+    }
+
+    // 湖を埋めたグリッドを作る
+    // island を指定するとその島の湖だけ、Noneなら全ての湖を fill で埋める
+    pub fn fill_holes<T: Clone>(&self, grid: &Grid<T>, island: Option<usize>, fill: T) -> Grid<T> {
+        let mut filled = grid.clone();
+        for (water, enclosed_by) in self.water_enclosed_by.iter().enumerate() {
+            let target = match (enclosed_by, island) {
+                (Some(_), None) => true,
+                (Some(owner), Some(island)) => *owner == island,
+                (None, _) => false,
+            };
+            if target {
+                for &cell in &self.water.islands[water].cells {
+                    filled[cell] = fill.clone();
+                }
+            }
+        }
+        filled
+    }
+}
+
+// 領域（島か海）を表す番号
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Region {
+    Land(usize),
+    Water(usize),
+}
+
+// 湖を検出する
+// connectivity は陸地のつながり方（海はその逆のつながり方で扱う）
+pub fn find_lakes<T, F>(grid: &Grid<T>, is_land: F, connectivity: Connectivity) -> LakeAnalysis
+where
+    F: Fn(&T) -> bool,
+{
+    let water_connectivity = match connectivity {
+        Connectivity::Four => Connectivity::Eight,
+        Connectivity::Eight => Connectivity::Four,
+    };
+    let land = label_components(grid, &is_land, connectivity);
+    let water = label_components(grid, |cell| !is_land(cell), water_connectivity);
+
+    let region_at = |pos: (usize, usize)| match (land.labels[pos], water.labels[pos]) {
+        (Some(l), _) => Region::Land(l),
+        (None, Some(w)) => Region::Water(w),
+        (None, None) => unreachable!("陸地でも海でもないマスはありません"),
+    };
+
+    let mut land_seen = vec![false; land.count()];
+    let mut water_enclosed_by: Vec<Option<Option<usize>>> = vec![None; water.count()];
+    let mut queue = VecDeque::new();
+
+    // 地図の端に接している領域は、地図の外から見つかる
+    // 端の海は外洋（囲んでいる島なし）
+    for pos in grid.positions() {
+        let (row, col) = pos;
+        let on_border = row == 0 || col == 0 || row + 1 == grid.rows() || col + 1 == grid.cols();
+        if !on_border {
+            continue;
+        }
+        match region_at(pos) {
+            Region::Land(l) if !land_seen[l] => {
+                land_seen[l] = true;
+                queue.push_back(Region::Land(l));
+            }
+            Region::Water(w) if water_enclosed_by[w].is_none() => {
+                water_enclosed_by[w] = Some(None);
+                queue.push_back(Region::Water(w));
+            }
+            _ => {}
+        }
+    }
+
+    // 外側から順に、隣り合う領域をたどる
+    while let Some(region) = queue.pop_front() {
+        let cells = match region {
+            Region::Land(l) => &land.islands[l].cells,
+            Region::Water(w) => &water.islands[w].cells,
+        };
+        for &cell in cells {
+            for next in grid.neighbors4(cell) {
+                match (region, region_at(next)) {
+                    // 島から初めて見つかった海は、その島の湖
+                    (Region::Land(l), Region::Water(w)) if water_enclosed_by[w].is_none() => {
+                        water_enclosed_by[w] = Some(Some(l));
+                        queue.push_back(Region::Water(w));
+                    }
+                    // 海から初めて見つかった島（湖の中の島）
+                    (Region::Water(_), Region::Land(l)) if !land_seen[l] => {
+                        land_seen[l] = true;
+                        queue.push_back(Region::Land(l));
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    let water_enclosed_by: Vec<Option<usize>> = water_enclosed_by
+        .into_iter()
+        .map(|owner| owner.flatten())
+        .collect();
+    let mut holes = vec![Vec::new(); land.count()];
+    for (w, owner) in water_enclosed_by.iter().enumerate() {
+        if let Some(island) = owner {
+            holes[*island].push(w);
+        }
+    }
+
+    LakeAnalysis {
+        land,
+        water,
+        water_enclosed_by,
+        holes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nested_lakes() {
+        let grid = Grid::parse(
+            "\
+.........
+.#######.
+.#.....#.
+.#.###.#.
+.#.#.#.#.
+.#.###.#.
+.#.....#.
+.#######.
+.........",
+        )
+        .unwrap();
+        let analysis = find_lakes(&grid, |&c| c == '#', Connectivity::Four);
+        assert_eq!(analysis.land.count(), 2);
+        // 外側の輪に湖が1つ、内側の輪にも湖が1つ
+        assert_eq!(analysis.hole_count(0), 1);
+        assert_eq!(analysis.hole_count(1), 1);
+        assert_eq!(analysis.lake_cells().len(), 16 + 1);
+
+        // 外側の島の湖だけ埋めると、内側の島も含めて1つの塊になる
+        let filled = analysis.fill_holes(&grid, Some(0), '#');
+        assert_eq!(filled[(4, 4)], '.');
+        let all = analysis.fill_holes(&grid, None, '#');
+        assert_eq!(all.find_all(|&c| c == '#').len(), 49);
+    }
+
+    #[test]
+    fn test_diagonal_gap_is_not_a_lake() {
+        // 4近傍の島は斜めの隙間から海が出入りできる
+        let grid = Grid::parse(
+            "\
+.##..
+#..#.
+#..#.
+.##..",
+        )
+        .unwrap();
+        let four = find_lakes(&grid, |&c| c == '#', Connectivity::Four);
+        assert!(four.lake_cells().is_empty());
+        let eight = find_lakes(&grid, |&c| c == '#', Connectivity::Eight);
+        assert_eq!(eight.land.count(), 1);
+        assert_eq!(eight.hole_count(0), 1);
+        assert_eq!(eight.lake_cells().len(), 4);
+    }
+
+    #[test]
+    fn test_lake_with_all_land_border() {
+        let grid = Grid::parse("###\n#.#\n###").unwrap();
+        let analysis = find_lakes(&grid, |&c| c == '#', Connectivity::Four);
+        assert_eq!(analysis.hole_count(0), 1);
+        assert!(analysis.is_lake(0));
+    }
+}
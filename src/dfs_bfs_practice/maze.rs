@@ -0,0 +1,283 @@
+use std::collections::VecDeque;
+
+use super::grid::Grid;
+use super::path::{trace_path, GridPath};
+use super::topology::{open_neighbors, Orthogonal, Topology};
+use crate::trace::SearchObserver;
+
+// =============================================================================
+// 🧭 迷路の最短距離（BFS）
+// =============================================================================
+//
+// '#' = 壁, '.' = 通路, 'S' = スタート, 'G' = ゴール
+// maze_shortest_path_bfs.rs の main に直接書いていたBFSを関数にしたものです。
+// observerを渡すと、探索の様子（現在地・移動先）を表示したり数えたりできます。
+// bfs_shortest_path は距離だけでなく経路（通るマスの列）も返します。
+// bfs_shortest_path_on は隣のマスの決め方（topology.rs）を差し替えられます。
+// dfs_find_path は比べるためのDFS版です（ゴールには着くが、最短とは限らない）。
+
+// 探索の結果: 経路と、調べたマス（BFSはキューに入れたマス、DFSはスタックから取り出したマス）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MazeSolution {
+    // 最短経路（到達できなければNone）
+    pub path: Option<GridPath>,
+    pub visited: Grid<bool>,
+}
+
+// スタートからゴールまでの最短距離をBFSで求める
+// 到達できない場合はNone
+pub fn bfs_shortest_distance<O: SearchObserver<(usize, usize)>>(
+    maze: &Grid<char>,
+    start: (usize, usize),
+    goal: (usize, usize),
+    observer: &mut O,
+) -> Option<usize> {
+    bfs_shortest_path(maze, start, goal, observer)
+        .path
+        .map(|path| path.steps())
+}
+
+// スタートからゴールまでの最短経路をBFSで求める
+// 各マスに「どこから来たか（親）」を記録して、ゴールから逆にたどる
+pub fn bfs_shortest_path<O: SearchObserver<(usize, usize)>>(
+    maze: &Grid<char>,
+    start: (usize, usize),
+    goal: (usize, usize),
+    observer: &mut O,
+) -> MazeSolution {
+    bfs_shortest_path_on(maze, &Orthogonal, start, goal, |&c| c != '#', observer)
+}
+
+// つながり方（トポロジー）を指定できるBFS
+// passable(値) が true のマスだけを通る
+pub fn bfs_shortest_path_on<T, Topo, F, O>(
+    maze: &Grid<T>,
+    topology: &Topo,
+    start: (usize, usize),
+    goal: (usize, usize),
+    passable: F,
+    observer: &mut O,
+) -> MazeSolution
+where
+    Topo: Topology + ?Sized,
+    F: Fn(&T) -> bool,
+    O: SearchObserver<(usize, usize)>,
+{
+    // キュー: 次に調べる場所を入れておく
+    let mut queue = VecDeque::new();
+
+    // 訪問済みフラグ: 同じ場所を2度調べないようにする
+    let mut visited = Grid::new(maze.rows(), maze.cols(), false);
+
+    // 親: parent[マス] = そのマスに来る直前のマス（スタートはNone）
+    let mut parent: Grid<Option<(usize, usize)>> = Grid::new(maze.rows(), maze.cols(), None);
+
+    // 隣のマスを入れる入れ物（マスごとに作り直さず使い回す）
+    let mut neighbors = Vec::new();
+
+    // スタート地点をキューに入れる（位置と距離）
+    queue.push_back((start, 0));
+    visited[start] = true;
+    observer.on_visit(start, 0);
+
+    while let Some((current_position, distance)) = queue.pop_front() {
+        observer.on_pop(current_position, distance as u64);
+
+        // ゴールに到着したら、親をたどって経路を作る
+        if current_position == goal {
+            let path = GridPath {
+                cost: distance as u64,
+                cells: trace_path(&parent, goal),
+            };
+            return MazeSolution {
+                path: Some(path),
+                visited,
+            };
+        }
+
+        // 通れる隣のマス（迷路の範囲外と壁はopen_neighborsが除いてくれる）
+        open_neighbors(maze, topology, current_position, &passable, &mut neighbors);
+        for &next in &neighbors {
+            // すでに訪問済みじゃないか？
+            if visited[next] {
+                continue;
+            }
+
+            visited[next] = true;
+            parent[next] = Some(current_position);
+            observer.on_relax(current_position, next, distance as u64 + 1);
+            observer.on_visit(next, distance as u64 + 1);
+            queue.push_back((next, distance + 1));
+        }
+    }
+
+    MazeSolution {
+        path: None,
+        visited,
+    }
+}
+
+// スタートからゴールまでの経路をDFSで探す（最短とは限らない）
+// 訪問済みにするのは、スタックに積むときではなく取り出したとき
+// （積むときに印を付けると、スタック順に広がるだけのBFSもどきになって奥へ進まない）
+// 同じマスが何度も積まれることがあるので、取り出したマスが訪問済みなら飛ばす（on_skip_stale）
+// 親は取り出したときに決める = 実際にそのマスへ進んできた元のマス
+pub fn dfs_find_path<O: SearchObserver<(usize, usize)>>(
+    maze: &Grid<char>,
+    start: (usize, usize),
+    goal: (usize, usize),
+    observer: &mut O,
+) -> MazeSolution {
+    // スタック: (マス, 来た元のマス, 距離)
+    let mut stack = vec![(start, None, 0)];
+    let mut visited = Grid::new(maze.rows(), maze.cols(), false);
+    let mut parent: Grid<Option<(usize, usize)>> = Grid::new(maze.rows(), maze.cols(), None);
+    let mut neighbors = Vec::new();
+
+    while let Some((current_position, from, distance)) = stack.pop() {
+        observer.on_pop(current_position, distance as u64);
+        if visited[current_position] {
+            observer.on_skip_stale(current_position, distance as u64);
+            continue;
+        }
+        visited[current_position] = true;
+        parent[current_position] = from;
+        observer.on_visit(current_position, distance as u64);
+
+        if current_position == goal {
+            let path = GridPath {
+                cost: distance as u64,
+                cells: trace_path(&parent, goal),
+            };
+            return MazeSolution {
+                path: Some(path),
+                visited,
+            };
+        }
+        open_neighbors(
+            maze,
+            &Orthogonal,
+            current_position,
+            |&c: &char| c != '#',
+            &mut neighbors,
+        );
+        for &next in &neighbors {
+            if visited[next] {
+                continue;
+            }
+            observer.on_relax(current_position, next, distance as u64 + 1);
+            stack.push((next, Some(current_position), distance + 1));
+        }
+    }
+
+    MazeSolution {
+        path: None,
+        visited,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trace::{JsonLinesTrace, NoTrace, TraceCounter};
+
+    fn sample_maze() -> Grid<char> {
+        Grid::parse("S.#..\n..#.#\n.#...\n...#.\n#...G").unwrap()
+    }
+
+    #[test]
+    fn test_bfs_with_counter() {
+        let maze = sample_maze();
+        let start = maze.find(|&c| c == 'S').unwrap();
+        let goal = maze.find(|&c| c == 'G').unwrap();
+        let mut counter = TraceCounter::default();
+        assert_eq!(
+            bfs_shortest_distance(&maze, start, goal, &mut counter),
+            Some(8)
+        );
+        // BFSではキューに入れるのは1回だけなので、古い情報のスキップは起きない
+        assert_eq!(counter.stale_skips, 0);
+        assert_eq!(counter.visits, counter.relaxations + 1);
+    }
+
+    #[test]
+    fn test_path_reconstruction() {
+        let maze = sample_maze();
+        let solution = bfs_shortest_path(&maze, (0, 0), (4, 4), &mut NoTrace);
+        let path = solution.path.unwrap();
+        assert_eq!(path.steps(), 8);
+        assert_eq!(path.cells.first(), Some(&(0, 0)));
+        assert_eq!(path.cells.last(), Some(&(4, 4)));
+        for pair in path.cells.windows(2) {
+            assert_eq!(
+                pair[0].0.abs_diff(pair[1].0) + pair[0].1.abs_diff(pair[1].1),
+                1
+            );
+            assert_ne!(maze[pair[1]], '#');
+        }
+        assert!(path.cells.iter().all(|&cell| solution.visited[cell]));
+
+        let blocked = Grid::parse("S#G").unwrap();
+        let solution = bfs_shortest_path(&blocked, (0, 0), (0, 2), &mut NoTrace);
+        assert_eq!(solution.path, None);
+        assert_eq!(solution.visited.cells(), &[true, false, false]);
+    }
+
+    #[test]
+    fn test_dfs_path_is_valid_but_not_shortest() {
+        let maze = Grid::parse("S...\n.##.\n...G").unwrap();
+        let path = dfs_find_path(&maze, (0, 0), (2, 3), &mut NoTrace)
+            .path
+            .unwrap();
+        assert_eq!(path.cells.first(), Some(&(0, 0)));
+        assert_eq!(path.cells.last(), Some(&(2, 3)));
+        assert_eq!(path.steps(), path.cells.len() - 1);
+        for pair in path.cells.windows(2) {
+            assert_eq!(
+                pair[0].0.abs_diff(pair[1].0) + pair[0].1.abs_diff(pair[1].1),
+                1
+            );
+        }
+        assert!(
+            path.steps() >= bfs_shortest_distance(&maze, (0, 0), (2, 3), &mut NoTrace).unwrap()
+        );
+        assert_eq!(
+            dfs_find_path(&Grid::parse("S#G").unwrap(), (0, 0), (0, 2), &mut NoTrace).path,
+            None
+        );
+    }
+
+    #[test]
+    fn test_dfs_goes_deep_before_backtracking() {
+        // 何もない3×3で、スタートのすぐ下を目指す。本当のDFSは最後に積んだ隣（右）から奥へ進み、
+        // ぐるっと回ってから下のマスに着く（積むときに印を付ける版は1歩で着いてしまう）
+        let maze = Grid::parse("S..\n...\n...").unwrap();
+        let solution = dfs_find_path(&maze, (0, 0), (1, 0), &mut NoTrace);
+        let path = solution.path.unwrap();
+        assert_eq!(
+            path.cells,
+            vec![(0, 0), (0, 1), (0, 2), (1, 2), (1, 1), (1, 0)]
+        );
+        // 訪問済みになるのは取り出したマスだけ（積まれただけの (2, 2) などは含まない）
+        assert_eq!(solution.visited.cells().iter().filter(|&&v| v).count(), 6);
+    }
+
+    #[test]
+    fn test_json_lines_trace() {
+        let maze = Grid::parse("S.G").unwrap();
+        let mut trace = JsonLinesTrace::new(Vec::new());
+        bfs_shortest_distance(&maze, (0, 0), (0, 2), &mut trace);
+        let output = String::from_utf8(trace.into_inner()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], r#"{"event":"visit","node":[0,0],"cost":0}"#);
+        assert_eq!(lines[1], r#"{"event":"pop","node":[0,0],"cost":0}"#);
+        assert_eq!(
+            lines[2],
+            r#"{"event":"relax","from":[0,0],"to":[0,1],"cost":1}"#
+        );
+        assert_eq!(
+            lines.last(),
+            Some(&r#"{"event":"pop","node":[0,2],"cost":2}"#)
+        );
+    }
+}
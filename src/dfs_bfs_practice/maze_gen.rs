@@ -0,0 +1,374 @@
+use super::grid::Grid;
+use crate::rng::XorShift64;
+use crate::union_find::UnionFind;
+
+// =============================================================================
+// 🏗️ 迷路の生成
+// =============================================================================
+//
+// シード付きの乱数で、'S' / 'G' / '#' / '.' の迷路を作ります。
+//
+// 迷路は「部屋」と「部屋の間の壁」でできていると考えます。
+// 部屋 (r, c) はグリッドの (2r+1, 2c+1) にあり、隣の部屋との間の壁を
+// 壊す（'.' にする）と通路になります。
+//
+//   #####
+//   #S..#    部屋は2×2、(0,0)と(0,1)の間の壁を壊した
+//   ###.#
+//   #G..#
+//   #####
+//
+// 壊す壁の選び方で、迷路の雰囲気が変わります。
+// - 穴掘り法（再帰的バックトラック） … 長い一本道が多い
+// - クラスカル法（Union-Find）         … 壁をランダムな順に見て、別のグループなら壊す
+// - プリム法                           … 迷路の端からランダムに広げていく
+// - ウィルソン法                       … ループを消したランダムウォーク（偏りのない迷路）
+//
+// どの方法でも全ての部屋がちょうど1通りの道でつながる（完全迷路）ので、
+// SからGへは必ず行けます。braid を指定すると行き止まりの壁を壊してループを作ります。
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MazeAlgorithm {
+    RecursiveBacktracker,
+    Kruskal,
+    Prim,
+    Wilson,
+}
+
+impl MazeAlgorithm {
+    pub const ALL: [MazeAlgorithm; 4] = [
+        MazeAlgorithm::RecursiveBacktracker,
+        MazeAlgorithm::Kruskal,
+        MazeAlgorithm::Prim,
+        MazeAlgorithm::Wilson,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            MazeAlgorithm::RecursiveBacktracker => "穴掘り法",
+            MazeAlgorithm::Kruskal => "クラスカル法",
+            MazeAlgorithm::Prim => "プリム法",
+            MazeAlgorithm::Wilson => "ウィルソン法",
+        }
+    }
+}
+
+// 部屋の並び（部屋の数と、部屋とグリッドの座標の変換）
+struct Rooms {
+    rows: usize,
+    cols: usize,
+}
+
+impl Rooms {
+    fn count(&self) -> usize {
+        self.rows * self.cols
+    }
+
+    fn id(&self, (r, c): (usize, usize)) -> usize {
+        r * self.cols + c
+    }
+
+    fn room(&self, id: usize) -> (usize, usize) {
+        (id / self.cols, id % self.cols)
+    }
+
+    // 上下左右の隣の部屋
+    fn neighbors(&self, (r, c): (usize, usize)) -> Vec<(usize, usize)> {
+        let mut result = Vec::with_capacity(4);
+        if r + 1 < self.rows {
+            result.push((r + 1, c));
+        }
+        if c + 1 < self.cols {
+            result.push((r, c + 1));
+        }
+        if r > 0 {
+            result.push((r - 1, c));
+        }
+        if c > 0 {
+            result.push((r, c - 1));
+        }
+        result
+    }
+}
+
+// 部屋のグリッド上の座標
+fn cell((r, c): (usize, usize)) -> (usize, usize) {
+    (2 * r + 1, 2 * c + 1)
+}
+
+// 隣り合う2つの部屋の間の壁のグリッド上の座標
+fn wall_between(a: (usize, usize), b: (usize, usize)) -> (usize, usize) {
+    let (ca, cb) = (cell(a), cell(b));
+    ((ca.0 + cb.0) / 2, (ca.1 + cb.1) / 2)
+}
+
+fn carve(maze: &mut Grid<char>, a: (usize, usize), b: (usize, usize)) {
+    maze[cell(a)] = '.';
+    maze[cell(b)] = '.';
+    maze[wall_between(a, b)] = '.';
+}
+
+// 迷路を作る
+// rows × cols はグリッド全体の大きさ。縦横とも3以上で、部屋が2つ以上できる大きさ（3×5 か 5×3 以上）が必要
+// 部屋は縦に (rows - 1) / 2 個、横に (cols - 1) / 2 個できる。偶数のときは右端・下端が壁のまま残る
+// braid は行き止まりを壊す確率（0.0 = 完全迷路、1.0 = 行き止まりなし）
+pub fn generate_maze(
+    rng: &mut XorShift64,
+    rows: usize,
+    cols: usize,
+    algorithm: MazeAlgorithm,
+    braid: f64,
+) -> Result<Grid<char>, String> {
+    if rows < 3 || cols < 3 {
+        return Err(format!(
+            "迷路は縦横とも3以上にしてください（{}×{}）",
+            rows, cols
+        ));
+    }
+    if !(0.0..=1.0).contains(&braid) {
+        return Err(format!("braid は0.0〜1.0で指定してください（{}）", braid));
+    }
+
+    let rooms = Rooms {
+        rows: (rows - 1) / 2,
+        cols: (cols - 1) / 2,
+    };
+    if rooms.count() < 2 {
+        return Err(format!(
+            "SとGを別の部屋に置けません（{}×{}）。部屋が2つ以上入るように、どちらかの辺を5以上にしてください",
+            rows, cols
+        ));
+    }
+    let mut maze = Grid::new(rows, cols, '#');
+    maze[cell((0, 0))] = '.';
+    match algorithm {
+        MazeAlgorithm::RecursiveBacktracker => recursive_backtracker(rng, &rooms, &mut maze),
+        MazeAlgorithm::Kruskal => kruskal(rng, &rooms, &mut maze),
+        MazeAlgorithm::Prim => prim(rng, &rooms, &mut maze),
+        MazeAlgorithm::Wilson => wilson(rng, &rooms, &mut maze),
+    }
+    if braid > 0.0 {
+        remove_dead_ends(rng, &rooms, &mut maze, braid);
+    }
+
+    maze[cell((0, 0))] = 'S';
+    maze[cell((rooms.rows - 1, rooms.cols - 1))] = 'G';
+    Ok(maze)
+}
+
+// 穴掘り法: 行けるところまで掘り進み、行き止まったら戻る
+// 再帰の代わりにVecをスタックとして使う（大きな迷路でもスタックが溢れない）
+fn recursive_backtracker(rng: &mut XorShift64, rooms: &Rooms, maze: &mut Grid<char>) {
+    let mut visited = vec![false; rooms.count()];
+    let mut stack = vec![(0, 0)];
+    visited[0] = true;
+
+    while let Some(&current) = stack.last() {
+        let unvisited: Vec<(usize, usize)> = rooms
+            .neighbors(current)
+            .into_iter()
+            .filter(|&next| !visited[rooms.id(next)])
+            .collect();
+        if unvisited.is_empty() {
+            stack.pop();
+            continue;
+        }
+        let next = unvisited[rng.below(unvisited.len())];
+        visited[rooms.id(next)] = true;
+        carve(maze, current, next);
+        stack.push(next);
+    }
+}
+
+// クラスカル法: 全ての壁をランダムな順に見て、両側が別のグループなら壊す
+fn kruskal(rng: &mut XorShift64, rooms: &Rooms, maze: &mut Grid<char>) {
+    let mut walls = Vec::new();
+    for id in 0..rooms.count() {
+        let (r, c) = rooms.room(id);
+        if r + 1 < rooms.rows {
+            walls.push(((r, c), (r + 1, c)));
+        }
+        if c + 1 < rooms.cols {
+            walls.push(((r, c), (r, c + 1)));
+        }
+    }
+    rng.shuffle(&mut walls);
+
+    let mut groups = UnionFind::new(rooms.count());
+    for (a, b) in walls {
+        if groups.union(rooms.id(a), rooms.id(b)) {
+            carve(maze, a, b);
+        }
+    }
+}
+
+// プリム法: 迷路に入った部屋に面する壁（候補）からランダムに1つ選んで広げる
+fn prim(rng: &mut XorShift64, rooms: &Rooms, maze: &mut Grid<char>) {
+    let mut in_maze = vec![false; rooms.count()];
+    let start = rooms.room(rng.below(rooms.count()));
+    in_maze[rooms.id(start)] = true;
+    maze[cell(start)] = '.';
+    let mut frontier: Vec<((usize, usize), (usize, usize))> = rooms
+        .neighbors(start)
+        .into_iter()
+        .map(|next| (start, next))
+        .collect();
+
+    while !frontier.is_empty() {
+        let (from, to) = frontier.swap_remove(rng.below(frontier.len()));
+        if in_maze[rooms.id(to)] {
+            continue;
+        }
+        in_maze[rooms.id(to)] = true;
+        carve(maze, from, to);
+        for next in rooms.neighbors(to) {
+            if !in_maze[rooms.id(next)] {
+                frontier.push((to, next));
+            }
+        }
+    }
+}
+
+// ウィルソン法: 迷路に入っていない部屋からランダムウォークし、
+// 迷路にぶつかったら、ループを消した道を迷路に加える
+fn wilson(rng: &mut XorShift64, rooms: &Rooms, maze: &mut Grid<char>) {
+    let mut in_maze = vec![false; rooms.count()];
+    let first = rng.below(rooms.count());
+    in_maze[first] = true;
+    maze[cell(rooms.room(first))] = '.';
+    // next_room[部屋] = ランダムウォークでその部屋から最後に進んだ部屋
+    // 同じ部屋を再び通ると上書きされるので、ループが自然に消える
+    let mut next_room = vec![0; rooms.count()];
+
+    for start in 0..rooms.count() {
+        if in_maze[start] {
+            continue;
+        }
+        let mut current = start;
+        while !in_maze[current] {
+            let neighbors = rooms.neighbors(rooms.room(current));
+            let next = rooms.id(neighbors[rng.below(neighbors.len())]);
+            next_room[current] = next;
+            current = next;
+        }
+        // ループを消した道をたどって迷路に加える
+        let mut current = start;
+        while !in_maze[current] {
+            in_maze[current] = true;
+            carve(maze, rooms.room(current), rooms.room(next_room[current]));
+            current = next_room[current];
+        }
+    }
+}
+
+// 行き止まり（3方向が壁の部屋）を確率braidで壊してループを作る
+fn remove_dead_ends(rng: &mut XorShift64, rooms: &Rooms, maze: &mut Grid<char>, braid: f64) {
+    for id in 0..rooms.count() {
+        let room = rooms.room(id);
+        let neighbors = rooms.neighbors(room);
+        let walls: Vec<(usize, usize)> = neighbors
+            .into_iter()
+            .filter(|&next| maze[wall_between(room, next)] == '#')
+            .collect();
+        // 通路が1本だけ = 行き止まり（他の壁を壊した結果、もう行き止まりでない場合もある）
+        let open = rooms.neighbors(room).len() - walls.len();
+        if open == 1 && !walls.is_empty() && rng.chance(braid) {
+            let next = walls[rng.below(walls.len())];
+            carve(maze, room, next);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dfs_bfs_practice::maze::bfs_shortest_distance;
+    use crate::trace::NoTrace;
+
+    // 迷路の通路の数（壊した壁の数）
+    fn passages(maze: &Grid<char>, rooms: &Rooms) -> usize {
+        (0..rooms.count())
+            .flat_map(|id| {
+                let room = rooms.room(id);
+                rooms
+                    .neighbors(room)
+                    .into_iter()
+                    .filter(move |&next| next > room)
+                    .map(move |next| (room, next))
+            })
+            .filter(|&(a, b)| maze[wall_between(a, b)] != '#')
+            .count()
+    }
+
+    #[test]
+    fn test_every_maze_is_solvable_and_perfect() {
+        let mut rng = XorShift64::new(42);
+        for algorithm in MazeAlgorithm::ALL {
+            for &(rows, cols) in &[(3, 5), (5, 3), (5, 9), (11, 21), (12, 8), (31, 31)] {
+                let maze = generate_maze(&mut rng, rows, cols, algorithm, 0.0).unwrap();
+                let start = maze.find(|&c| c == 'S').unwrap();
+                let goal = maze.find(|&c| c == 'G').unwrap();
+                assert!(
+                    bfs_shortest_distance(&maze, start, goal, &mut NoTrace).is_some(),
+                    "{:?}\n{}",
+                    algorithm,
+                    maze
+                );
+                // 完全迷路: 全ての部屋が木の形でつながる（通路の数 = 部屋の数 - 1）
+                let rooms = Rooms {
+                    rows: (rows - 1) / 2,
+                    cols: (cols - 1) / 2,
+                };
+                assert_eq!(
+                    passages(&maze, &rooms),
+                    rooms.count() - 1,
+                    "{:?}",
+                    algorithm
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_braid_removes_dead_ends() {
+        let mut rng = XorShift64::new(7);
+        for algorithm in MazeAlgorithm::ALL {
+            let maze = generate_maze(&mut rng, 21, 21, algorithm, 1.0).unwrap();
+            let rooms = Rooms { rows: 10, cols: 10 };
+            assert!(passages(&maze, &rooms) > rooms.count() - 1);
+            for id in 0..rooms.count() {
+                let room = rooms.room(id);
+                let open = rooms
+                    .neighbors(room)
+                    .into_iter()
+                    .filter(|&next| maze[wall_between(room, next)] != '#')
+                    .count();
+                assert!(
+                    open >= 2,
+                    "{:?}: 行き止まり {:?}\n{}",
+                    algorithm,
+                    room,
+                    maze
+                );
+            }
+            let goal = maze.find(|&c| c == 'G').unwrap();
+            assert!(bfs_shortest_distance(&maze, (1, 1), goal, &mut NoTrace).is_some());
+        }
+    }
+
+    #[test]
+    fn test_same_seed_same_maze() {
+        let a = generate_maze(&mut XorShift64::new(1), 15, 15, MazeAlgorithm::Wilson, 0.3).unwrap();
+        let b = generate_maze(&mut XorShift64::new(1), 15, 15, MazeAlgorithm::Wilson, 0.3).unwrap();
+        assert_eq!(a, b);
+        assert!(generate_maze(&mut XorShift64::new(1), 2, 9, MazeAlgorithm::Prim, 0.0).is_err());
+        assert!(generate_maze(&mut XorShift64::new(1), 4, 4, MazeAlgorithm::Prim, 0.0).is_err());
+        // 部屋が2つ入れば、縦長でも横長でも作れる
+        assert!(generate_maze(&mut XorShift64::new(1), 3, 5, MazeAlgorithm::Prim, 0.0).is_ok());
+        assert!(generate_maze(&mut XorShift64::new(1), 5, 3, MazeAlgorithm::Prim, 0.0).is_ok());
+        let error =
+            generate_maze(&mut XorShift64::new(1), 3, 3, MazeAlgorithm::Prim, 0.0).unwrap_err();
+        assert!(error.contains("5以上"), "{}", error);
+        assert!(generate_maze(&mut XorShift64::new(1), 9, 9, MazeAlgorithm::Prim, 1.5).is_err());
+    }
+}
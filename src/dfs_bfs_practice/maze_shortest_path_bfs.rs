@@ -1,123 +1,468 @@
-use std::collections::{VecDeque, HashMap};
+use std::env;
+use std::io;
+use std::time::Duration;
+
+use rust_learning::dfs_bfs_practice::animate::{dump_frames, play, SearchRecording};
+use rust_learning::dfs_bfs_practice::astar::{astar, jump_point_search, Heuristic, STRAIGHT_COST};
+use rust_learning::dfs_bfs_practice::bidirectional::bidirectional_bfs;
+use rust_learning::dfs_bfs_practice::grid::Grid;
+use rust_learning::dfs_bfs_practice::labeling::Connectivity;
+use rust_learning::dfs_bfs_practice::maze::{
+    bfs_shortest_distance, bfs_shortest_path, dfs_find_path,
+};
+use rust_learning::dfs_bfs_practice::maze_gen::{generate_maze, MazeAlgorithm};
+use rust_learning::dfs_bfs_practice::path::{render_path, save_ppm};
+use rust_learning::dfs_bfs_practice::state_bfs::{all_keys, solve_key_maze};
+use rust_learning::dfs_bfs_practice::timed::{ping_pong, timed_astar, timed_bfs, ObstacleSchedule};
+use rust_learning::dfs_bfs_practice::voxel::{count_components_6, voxel_shortest_path, Grid3};
+use rust_learning::dfs_bfs_practice::weighted::{grid_dijkstra, zero_one_bfs, Terrain};
+use rust_learning::rng::XorShift64;
+use rust_learning::trace::{JsonLinesTrace, NoTrace, TextTrace, TraceCounter};
+
+// --json のときは標準出力をJSON Linesだけにするため、説明の文章は標準エラーに出す
+macro_rules! say {
+    ($json:expr, $($arg:tt)*) => {
+        if $json {
+            eprintln!($($arg)*)
+        } else {
+            println!($($arg)*)
+        }
+    };
+}
 
 fn main() {
-    println!("=== DFS/BFS学習: グリッド上の迷路探索 ===\n");
-    
+    let args: Vec<String> = env::args().collect();
+    let json = args.iter().any(|arg| arg == "--json");
+    say!(json, "=== DFS/BFS学習: グリッド上の迷路探索 ===\n");
+
     // 迷路の定義
     // '#' = 壁, '.' = 通路, 'S' = スタート, 'G' = ゴール
-    let maze = vec![
-        vec!['S', '.', '#', '.', '.'],
-        vec!['.', '.', '#', '.', '#'],
-        vec!['.', '#', '.', '.', '.'],
-        vec!['.', '.', '.', '#', '.'],
-        vec!['#', '.', '.', '.', 'G'],
-    ];
-    
-    println!("迷路:");
-    for row in &maze {
-        println!("{}", row.iter().collect::<String>());
-    }
-    println!();
-    
+    let maze = Grid::parse(
+        "\
+S.#..
+..#.#
+.#...
+...#.
+#...G",
+    )
+    .expect("迷路の形式が不正です");
+
+    say!(json, "迷路:");
+    say!(json, "{}", maze);
+
     // ステップ1: スタートとゴールの位置を見つける
-    let mut start = (0, 0);
-    let mut goal = (0, 0);
-    
-    for i in 0..maze.len() {
-        for j in 0..maze[0].len() {
-            if maze[i][j] == 'S' {
-                start = (i, j);
-                println!("スタート位置: ({}, {})", i, j);
+    let start = maze
+        .find(|&c| c == 'S')
+        .expect("迷路にスタートがありません");
+    let goal = maze.find(|&c| c == 'G').expect("迷路にゴールがありません");
+    say!(json, "スタート位置: ({}, {})", start.0, start.1);
+    say!(json, "ゴール位置: ({}, {})", goal.0, goal.1);
+    say!(json, "");
+
+    // ステップ2: BFSで探索する
+    // --json を付けると探索の様子をJSON Lines形式で標準出力に出す（説明の文章は標準エラーへ）
+    // --ppm <ファイル> を付けると経路を描いたPPM画像を書き出す
+    // --animate [bfs|dfs] を付けると探索の様子を色付きのアニメーションで表示する
+    //   （--delay <ミリ秒> で1コマの表示時間、既定は150）
+    // --frames <フォルダ> を付けるとアニメーションのコマを色なしのテキストで書き出す
+    // 表示と同時にTraceCounterで回数も数える（どちらも同じ通知を受け取る）
+    let value_of = |flag: &str| {
+        args.iter()
+            .position(|arg| arg == flag)
+            .and_then(|i| args.get(i + 1))
+    };
+    let frames_dir = value_of("--frames");
+    if args.iter().any(|arg| arg == "--animate") || frames_dir.is_some() {
+        let use_dfs = value_of("--animate").is_some_and(|name| name == "dfs");
+        let delay = match value_of("--delay").map(|ms| ms.parse::<u64>()) {
+            None => 150,
+            Some(Ok(ms)) => ms,
+            Some(Err(_)) => {
+                eprintln!("--delay にはミリ秒を整数で指定してください");
+                return;
             }
-            if maze[i][j] == 'G' {
-                goal = (i, j);
-                println!("ゴール位置: ({}, {})", i, j);
+        };
+        if let Err(e) = animate_search(
+            &maze,
+            start,
+            goal,
+            use_dfs,
+            frames_dir,
+            Duration::from_millis(delay),
+        ) {
+            eprintln!("エラー: {}", e);
+        }
+        return;
+    }
+    let ppm_file = value_of("--ppm");
+    let mut counter = TraceCounter::default();
+    let solution = if json {
+        let mut observer = (JsonLinesTrace::new(io::stdout()), &mut counter);
+        bfs_shortest_path(&maze, start, goal, &mut observer)
+    } else {
+        println!("BFSを開始します...");
+        println!("探索の様子:");
+        let mut observer = (TextTrace::new(io::stdout()), &mut counter);
+        bfs_shortest_path(&maze, start, goal, &mut observer)
+    };
+    if solution.path.is_some() && !json {
+        println!("    → ゴール発見！");
+    }
+
+    // 結果を表示
+    say!(json, "");
+    match &solution.path {
+        Some(path) => {
+            say!(json, "最短距離: {}", path.steps());
+            let route: Vec<String> = path
+                .cells
+                .iter()
+                .map(|(r, c)| format!("({}, {})", r, c))
+                .collect();
+            say!(json, "経路: {}", route.join(" → "));
+            if !json {
+                println!("経路（'*'）:");
+                print!("{}", render_path(&maze, path, None));
+                println!("調べたマスも表示（'o'）:");
+                print!("{}", render_path(&maze, path, Some(&solution.visited)));
+            }
+            if let Some(file) = ppm_file {
+                let image = render_path(&maze, path, Some(&solution.visited));
+                match save_ppm(file, &image, 16) {
+                    Ok(()) => say!(json, "PPM画像を書き出しました: {}", file),
+                    Err(e) => say!(json, "エラー: {}", e),
+                }
             }
         }
+        None => say!(json, "ゴールに到達できません"),
+    }
+    say!(
+        json,
+        "取り出したマス: {}, 訪問したマス: {}",
+        counter.pops,
+        counter.visits
+    );
+    if json {
+        return;
     }
+
+    // 地形ごとにコストが違う地図（ダイクストラ法）
+    // '.' = 道(1), ':' = 砂地(3), '~' = 沼(5), '#' = 壁
     println!();
-    
-    // ステップ2: BFSに必要な準備
-    // キュー: 次に調べる場所を入れておく
-    let mut queue = VecDeque::new();
-    
-    // 訪問済みフラグ: 同じ場所を2度調べないようにする
-    let mut visited: HashMap<(usize, usize), bool> = HashMap::new();
-    
-    // 4方向への移動（下、右、上、左）
-    // isizeを使うことで、負の値も扱える
-    let directions: [(isize, isize); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
-    
-    // ステップ3: BFSの開始
-    // スタート地点をキューに入れる（位置と距離）
-    queue.push_back((start, 0));
-    visited.insert(start, true);  // HashMapに訪問済みとして記録
-    
-    println!("BFSを開始します...");
-    println!("探索の様子:");
-    
-    // BFSのメインループ
-    let mut answer = None;  // 答えを保存する変数
-    
-    while !queue.is_empty() {
-        // ステップ1: キューから1つ取り出す
-        // キューには ((行, 列), 距離) が入っている
-        let (current_position, distance) = queue.pop_front().unwrap();
-        let (row, col) = current_position;  // 現在位置を分解
-        
-        println!("  現在地: ({}, {}) 距離: {}", row, col, distance);
-        
-        // ステップ2: ゴールに到着したかチェック
-        if current_position == goal {
-            println!("    → ゴール発見！");
-            answer = Some(distance);
-            break;  // ゴールに着いたので終了
-        }
-        
-        // ステップ3: 4方向を調べる
-        for (dr, dc) in directions.iter() {
-            // 新しい座標を計算
-            // usizeは0以上の値しか扱えないので、一旦isizeで計算
-            let new_row = row as isize + dr;
-            let new_col = col as isize + dc;
-            
-            // チェック1: 迷路の範囲内か？
-            if new_row < 0 || new_row >= maze.len() as isize {
-                continue;  // 範囲外なのでスキップ
+    println!("=== 地形コストつきの迷路（ダイクストラ法） ===");
+    let text = "\
+S.~~~..
+.#:::#.
+.#~.~#.
+...#..G";
+    let map = Grid::parse(text).expect("地図の形式が不正です");
+    let terrain = Grid::parse_with(text, &Terrain::legend()).expect("地図の形式が不正です");
+    let map_start = map.find(|&c| c == 'S').expect("地図にスタートがありません");
+    let map_goal = map.find(|&c| c == 'G').expect("地図にゴールがありません");
+    print!("{}", map);
+    match grid_dijkstra(&terrain, map_start, map_goal, |t| t.cost(), &mut NoTrace) {
+        Some(path) => {
+            let mut route = map.clone();
+            for &cell in &path.cells[1..path.cells.len() - 1] {
+                route[cell] = '*';
             }
-            if new_col < 0 || new_col >= maze[0].len() as isize {
-                continue;  // 範囲外なのでスキップ
+            println!("最小コスト: {}（{}マス）", path.cost, path.cells.len() - 1);
+            print!("{}", route);
+        }
+        None => println!("ゴールに到達できません"),
+    }
+
+    // 壁を壊しながら進む（0-1 BFS）: 通路はコスト0、壁を壊すとコスト1
+    println!();
+    println!("=== 壁を壊す回数を最小に（0-1 BFS） ===");
+    let walled = Grid::parse(
+        "\
+S.#..
+###.#
+..#.G",
+    )
+    .expect("迷路の形式が不正です");
+    print!("{}", walled);
+    let wall_cost = |&c: &char| Some(if c == '#' { 1 } else { 0 });
+    let walled_goal = walled
+        .find(|&c| c == 'G')
+        .expect("迷路にゴールがありません");
+    match zero_one_bfs(&walled, (0, 0), walled_goal, wall_cost, &mut NoTrace) {
+        Ok(Some(path)) => {
+            println!("壊す壁の数: {}", path.cost);
+            // 壊した壁は 'X'、通ったマスは '*'
+            let mut route = walled.clone();
+            for &cell in &path.cells[1..path.cells.len() - 1] {
+                route[cell] = if walled[cell] == '#' { 'X' } else { '*' };
             }
-            
-            // isizeからusizeに安全に変換（ここまで来れば必ず0以上）
-            let new_row = new_row as usize;
-            let new_col = new_col as usize;
-            
-            // チェック2: 壁じゃないか？
-            if maze[new_row][new_col] == '#' {
-                continue;  // 壁なのでスキップ
+            print!("{}", route);
+        }
+        Ok(None) => println!("ゴールに到達できません"),
+        Err(e) => println!("エラー: {}", e),
+    }
+
+    // 広い地図では、BFSはほぼ全てのマスを調べてしまう
+    // A*（ゴールへの見積もりつき）やJPSと、展開したマスの数を比べる
+    println!();
+    println!("=== 広い地図での展開数の比較（BFS / A* / JPS） ===");
+    let mut open_map = Grid::new(80, 80, '.');
+    for row in 10..70 {
+        open_map[(row, 40)] = '#';
+    }
+    let (open_start, open_goal) = ((40, 5), (40, 75));
+    println!(
+        "80×80の広場（中央に縦の壁）: ({}, {}) → ({}, {})",
+        40, 5, 40, 75
+    );
+
+    let mut bfs_counter = TraceCounter::default();
+    let bfs = bfs_shortest_distance(&open_map, open_start, open_goal, &mut bfs_counter);
+    println!(
+        "BFS（4近傍）: 距離 {}, 展開 {}",
+        bfs.map_or("-".to_string(), |d| d.to_string()),
+        bfs_counter.pops
+    );
+    let both = bidirectional_bfs(&open_map, open_start, open_goal, &mut NoTrace);
+    print_report(
+        "双方向BFS（4近傍）",
+        both.path.map(|p| p.cost * STRAIGHT_COST),
+        both.expanded,
+    );
+    let passable = |&c: &char| c != '#';
+    let searches = [
+        (
+            "A* マンハッタン（4近傍）",
+            Connectivity::Four,
+            Heuristic::Manhattan,
+        ),
+        (
+            "A* ユークリッド（8近傍）",
+            Connectivity::Eight,
+            Heuristic::Euclidean,
+        ),
+        (
+            "A* オクタイル（8近傍）",
+            Connectivity::Eight,
+            Heuristic::Octile,
+        ),
+        // 8近傍のマンハッタン距離は大きすぎる見積もりなので、astar がエラーにする
+        (
+            "A* マンハッタン（8近傍）",
+            Connectivity::Eight,
+            Heuristic::Manhattan,
+        ),
+    ];
+    for (name, connectivity, heuristic) in searches {
+        let report = astar(
+            &open_map,
+            open_start,
+            open_goal,
+            connectivity,
+            heuristic,
+            passable,
+            &mut NoTrace,
+        );
+        match report {
+            Ok(report) => print_report(name, report.path.map(|p| p.cost), report.expanded),
+            Err(e) => println!("{}: エラー: {}", name, e),
+        }
+    }
+    let jps = jump_point_search(&open_map, open_start, open_goal, passable);
+    print_report("JPS（8近傍）", jps.path.map(|p| p.cost), jps.expanded);
+
+    // 鍵と扉のある迷路: 状態 = (マス, 持っている鍵, スイッチ)
+    println!();
+    println!("=== 鍵と扉の迷路（状態空間のBFS） ===");
+    println!("小文字 = 鍵, 大文字 = 扉, '!' = スイッチ, '|' = ゲート");
+    let key_maze = Grid::parse(
+        "\
+S.#b..|.
+.a#.##.#
+..A..#.G
+##.!.B..",
+    )
+    .expect("迷路の形式が不正です");
+    print!("{}", key_maze);
+    for (name, required) in [
+        ("ゴールに着くだけ", 0),
+        ("全ての鍵を集めてゴール", all_keys(&key_maze)),
+    ] {
+        match solve_key_maze(&key_maze, required) {
+            Ok(Some(solution)) => {
+                let keys: String = solution.key_order().into_iter().collect();
+                println!("{}: {}手（鍵を拾った順: {}）", name, solution.steps(), keys);
             }
-            
-            // チェック3: すでに訪問済みじゃないか？
-            if visited.contains_key(&(new_row, new_col)) {
-                continue;  // 訪問済みなのでスキップ
+            Ok(None) => println!("{}: 到達できません", name),
+            Err(e) => println!("エラー: {}", e),
+        }
+    }
+
+    // 見回りロボットが動く床: 状態 = (マス, 時刻)、その場で待つこともできる
+    println!();
+    println!("=== 動く障害物を避ける（時間つきのBFS / A*） ===");
+    let floor = Grid::parse(
+        "\
+##.#.##
+S.....G
+##.#.##",
+    )
+    .expect("地図の形式が不正です");
+    print!("{}", floor);
+    // 見回り: 2か所で廊下を縦に横切る（2台目は廊下の上から動き始める）
+    let routes = vec![
+        ping_pong(&[(0, 2), (1, 2), (2, 2)]),
+        vec![(1, 4), (2, 4), (1, 4), (0, 4)],
+    ];
+    let schedule = ObstacleSchedule::periodic(routes).expect("見回りのルートが不正です");
+    let floor_start = floor
+        .find(|&c| c == 'S')
+        .expect("地図にスタートがありません");
+    let floor_goal = floor.find(|&c| c == 'G').expect("地図にゴールがありません");
+    let passable = |&c: &char| c != '#';
+    match timed_bfs(&floor, floor_start, floor_goal, &schedule, passable) {
+        Some(path) => {
+            println!(
+                "到着時刻: {}（待った回数: {}）",
+                path.arrival_time(),
+                path.waits()
+            );
+            for &((r, c), t) in &path.steps {
+                println!("  時刻{:>2}: ({}, {})", t, r, c);
             }
-            
-            // ここまで来たら、行ける場所！
-            println!("    → ({}, {}) へ移動可能", new_row, new_col);
-            
-            // 訪問済みにする
-            visited.insert((new_row, new_col), true);
-            
-            // キューに追加（距離は+1）
-            queue.push_back(((new_row, new_col), distance + 1));
         }
+        None => println!("ゴールに到達できません"),
     }
-    
-    // 結果を表示
+    if let Some(path) = timed_astar(&floor, floor_start, floor_goal, &schedule, passable) {
+        println!("A*でも到着時刻: {}", path.arrival_time());
+    }
+
+    // 同じシードなら同じ迷路ができる
     println!();
-    match answer {
-        Some(dist) => println!("最短距離: {}", dist),
+    println!("=== 迷路の自動生成（シード付き） ===");
+    for algorithm in MazeAlgorithm::ALL {
+        let mut rng = XorShift64::new(2024);
+        let generated =
+            generate_maze(&mut rng, 11, 21, algorithm, 0.0).expect("迷路を生成できません");
+        show_generated(algorithm.name(), &generated);
+    }
+    let mut rng = XorShift64::new(2024);
+    let braided = generate_maze(&mut rng, 11, 21, MazeAlgorithm::RecursiveBacktracker, 0.5)
+        .expect("迷路を生成できません");
+    show_generated("穴掘り法 + braid 0.5", &braided);
+
+    // 3階建ての倉庫: 階段 '^' / 'v' とエレベーター 'E' で階を移動する
+    println!();
+    println!("=== 複数階の倉庫（3次元のBFS） ===");
+    let warehouse = Grid3::parse(
+        "\
+S.#.E
+.##.#
+...^#
+
+####E
+#...#
+#.#v#
+
+..G.E
+.####
+.....",
+    )
+    .expect("倉庫の地図の形式が不正です");
+    match voxel_shortest_path(&warehouse) {
+        Ok(Some(path)) => {
+            println!("最短 {}手", path.len() - 1);
+            let cells: Vec<String> = path
+                .iter()
+                .map(|&(floor, row, col)| format!("{}階({}, {})", floor + 1, row, col))
+                .collect();
+            println!("{}", cells.join(" → "));
+        }
+        Ok(None) => println!("ゴールに到達できません"),
+        Err(e) => println!("エラー: {}", e),
+    }
+    let (count, largest) = count_components_6(&warehouse, |&c| c != '#');
+    println!(
+        "階段を使わず6方向でつながった通路: {}個（最大 {}マス）",
+        count, largest
+    );
+
+    // 大きな迷路で、片側のBFSと双方向BFSの展開数を比べる
+    let mut rng = XorShift64::new(7);
+    let large =
+        generate_maze(&mut rng, 201, 201, MazeAlgorithm::Prim, 0.1).expect("迷路を生成できません");
+    let large_start = large
+        .find(|&c| c == 'S')
+        .expect("迷路にスタートがありません");
+    let large_goal = large.find(|&c| c == 'G').expect("迷路にゴールがありません");
+    let mut one_sided = TraceCounter::default();
+    let one = bfs_shortest_distance(&large, large_start, large_goal, &mut one_sided);
+    let both = bidirectional_bfs(&large, large_start, large_goal, &mut NoTrace);
+    println!("201×201の迷路（プリム法 + braid 0.1）");
+    println!(
+        "片側のBFS: 距離 {}, 展開 {}",
+        one.map_or("-".to_string(), |d| d.to_string()),
+        one_sided.pops
+    );
+    println!(
+        "双方向BFS: 距離 {}, 展開 {}",
+        both.path.map_or("-".to_string(), |p| p.steps().to_string()),
+        both.expanded
+    );
+}
+
+// 探索を記録して、ターミナルで再生するか、コマをファイルに書き出す
+fn animate_search(
+    maze: &Grid<char>,
+    start: (usize, usize),
+    goal: (usize, usize),
+    use_dfs: bool,
+    frames_dir: Option<&String>,
+    delay: Duration,
+) -> Result<(), String> {
+    let mut recording = SearchRecording::new(maze);
+    let solution = if use_dfs {
+        dfs_find_path(maze, start, goal, &mut recording)
+    } else {
+        bfs_shortest_path(maze, start, goal, &mut recording)
+    };
+    if let Some(path) = &solution.path {
+        recording.set_path(path);
+    }
+    match frames_dir {
+        Some(dir) => {
+            let count = dump_frames(&recording, dir)?;
+            println!("{}コマを書き出しました: {}", count, dir);
+        }
+        None => play(&recording, &mut io::stdout(), delay)?,
+    }
+    match solution.path {
+        Some(path) => println!(
+            "{}: {}手でゴール",
+            if use_dfs { "DFS" } else { "BFS" },
+            path.steps()
+        ),
         None => println!("ゴールに到達できません"),
     }
-}
\ No newline at end of file
+    Ok(())
+}
+
+// 生成した迷路を最短経路つきで表示する
+fn show_generated(name: &str, maze: &Grid<char>) {
+    let start = maze
+        .find(|&c| c == 'S')
+        .expect("迷路にスタートがありません");
+    let goal = maze.find(|&c| c == 'G').expect("迷路にゴールがありません");
+    let path = bfs_shortest_path(maze, start, goal, &mut NoTrace)
+        .path
+        .expect("生成した迷路は必ず解ける");
+    println!("--- {}（最短 {}手） ---", name, path.steps());
+    print!("{}", render_path(maze, &path, None));
+}
+
+// 距離はまっすぐ1マスを1.0として表示する
+fn print_report(name: &str, cost: Option<u64>, expanded: usize) {
+    let distance = cost.map_or("-".to_string(), |c| {
+        format!("{:.3}", c as f64 / STRAIGHT_COST as f64)
+    });
+    println!("{}: 距離 {}, 展開 {}", name, distance, expanded);
+}
@@ -0,0 +1,27 @@
+// =============================================================================
+// 🗺️ DFS/BFS: グリッド探索の共通実装
+// =============================================================================
+//
+// 各練習ファイル（迷路、島の探索など）から共有して使う探索の実装です。
+
+pub mod animate;
+pub mod astar;
+pub mod bidirectional;
+pub mod bitset;
+pub mod dfs;
+pub mod distance_field;
+pub mod grid;
+pub mod island_tracker;
+pub mod labeling;
+pub mod lakes;
+pub mod maze;
+pub mod maze_gen;
+pub mod multi_source_bfs;
+pub mod path;
+pub mod region;
+pub mod state_bfs;
+pub mod streaming;
+pub mod timed;
+pub mod topology;
+pub mod voxel;
+pub mod weighted;
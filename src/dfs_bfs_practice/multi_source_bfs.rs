@@ -0,0 +1,267 @@
+use std::collections::VecDeque;
+
+use super::grid::Grid;
+use super::labeling::{label_components, Connectivity};
+
+// =============================================================================
+// 🌊 多始点BFS
+// =============================================================================
+//
+// 複数のスタート地点を最初に全部キューへ入れてからBFSを始めます。
+// 「どれか1つのスタートからの最短距離」が全マスについて1回で求まります。
+//
+//   距離0: 全てのスタート地点
+//   距離1: どれかのスタートから1手で行けるマス
+//   ...
+//
+// 島と島の間の橋の長さや、一番近い目標までの距離を求めるときに使います。
+
+// 多始点BFSの結果
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiSourceResult {
+    // 一番近いスタートからの距離（到達できないマスはNone）
+    pub distance: Grid<Option<usize>>,
+    // 一番近いスタートの番号（sources[i] の i）
+    pub nearest: Grid<Option<usize>>,
+    // 最短経路でどのマスから来たか（スタート地点はNone）
+    pub parent: Grid<Option<(usize, usize)>>,
+}
+
+impl MultiSourceResult {
+    // 一番近いスタートからcellまでの経路（スタート → cell の順）
+    // 到達できないマスなら空
+    pub fn path_to(&self, cell: (usize, usize)) -> Vec<(usize, usize)> {
+        if self.distance[cell].is_none() {
+            return Vec::new();
+        }
+        let mut path = vec![cell];
+        let mut current = cell;
+        while let Some(prev) = self.parent[current] {
+            path.push(prev);
+            current = prev;
+        }
+        path.reverse();
+        path
+    }
+}
+
+// 多始点BFS
+// passable(座標, 値) が true のマスだけを通る（スタート地点は必ず含める）
+pub fn multi_source_bfs<T, F>(
+    grid: &Grid<T>,
+    sources: &[(usize, usize)],
+    passable: F,
+) -> MultiSourceResult
+where
+    F: Fn((usize, usize), &T) -> bool,
+{
+    let mut result = MultiSourceResult {
+        distance: Grid::new(grid.rows(), grid.cols(), None),
+        nearest: Grid::new(grid.rows(), grid.cols(), None),
+        parent: Grid::new(grid.rows(), grid.cols(), None),
+    };
+    let mut queue = VecDeque::new();
+
+    // 全てのスタート地点を距離0でキューに入れる
+    for (i, &source) in sources.iter().enumerate() {
+        if result.distance[source].is_none() {
+            result.distance[source] = Some(0);
+            result.nearest[source] = Some(i);
+            queue.push_back(source);
+        }
+    }
+
+    while let Some(current) = queue.pop_front() {
+        let next_distance = result.distance[current].unwrap_or(0) + 1;
+        for next in grid.neighbors4(current) {
+            if result.distance[next].is_some() || !passable(next, &grid[next]) {
+                continue;
+            }
+            result.distance[next] = Some(next_distance);
+            result.nearest[next] = result.nearest[current];
+            result.parent[next] = Some(current);
+            queue.push_back(next);
+        }
+    }
+
+    result
+}
+
+// =============================================================================
+// 🌉 島と島の間の最短の橋
+// =============================================================================
+
+// 橋: 渡る海のマスの数と、そのマスの一覧（島1側から順に）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bridge {
+    pub length: usize,
+    pub cells: Vec<(usize, usize)>,
+}
+
+// startを含む島の全マスを求める（上下左右につながった陸地）
+pub fn island_cells<T, F>(grid: &Grid<T>, start: (usize, usize), is_land: F) -> Vec<(usize, usize)>
+where
+    F: Fn(&T) -> bool,
+{
+    if !is_land(&grid[start]) {
+        return Vec::new();
+    }
+    let result = multi_source_bfs(grid, &[start], |_, cell| is_land(cell));
+    grid.positions()
+        .filter(|&pos| result.distance[pos].is_some())
+        .collect()
+}
+
+// 島1から海を渡って島2に着くまでの最短の橋
+// 島1の全マスをスタートにして、海だけを通る多始点BFSを行い、
+// 島2のマスの隣に着いた時点の距離が橋の長さになる
+// 同じ島なら長さ0、どちらかが陸地でないか到達できなければNone
+pub fn shortest_bridge<T, F>(
+    grid: &Grid<T>,
+    island1_start: (usize, usize),
+    island2_start: (usize, usize),
+    is_land: F,
+) -> Option<Bridge>
+where
+    F: Fn(&T) -> bool,
+{
+    let island1 = island_cells(grid, island1_start, &is_land);
+    if island1.is_empty() || !is_land(&grid[island2_start]) {
+        return None;
+    }
+    if island1.contains(&island2_start) {
+        return Some(Bridge {
+            length: 0,
+            cells: Vec::new(),
+        });
+    }
+    let island2 = island_cells(grid, island2_start, &is_land);
+
+    let sea = multi_source_bfs(grid, &island1, |_, cell| !is_land(cell));
+    best_bridge_to(grid, &sea, &island2)
+}
+
+// 多始点BFSの結果から、targetの島へ渡る一番短い橋を選ぶ
+fn best_bridge_to<T>(
+    grid: &Grid<T>,
+    sea: &MultiSourceResult,
+    target: &[(usize, usize)],
+) -> Option<Bridge> {
+    // 島2の各マスの隣で、BFSが届いたマスのうち一番近いもの
+    let landing = target
+        .iter()
+        .flat_map(|&cell| grid.neighbors4(cell))
+        .filter_map(|pos| sea.distance[pos].map(|d| (d, pos)))
+        .min()?;
+
+    // 経路の先頭は島1の陸地なので除き、海のマスだけを橋とする
+    let cells: Vec<(usize, usize)> = sea.path_to(landing.1).into_iter().skip(1).collect();
+    Some(Bridge {
+        length: landing.0,
+        cells,
+    })
+}
+
+// 全ての島の組み合わせについての最短の橋の長さ
+#[derive(Debug, Clone, PartialEq)]
+pub struct BridgeMatrix {
+    // 各島のマス一覧（左上から見つけた順に島0, 島1, ...）
+    pub islands: Vec<Vec<(usize, usize)>>,
+    // lengths[i][j] = 島iと島jの間の最短の橋（渡れなければNone）
+    pub lengths: Vec<Vec<Option<usize>>>,
+}
+
+// 島ごとに1回ずつ多始点BFSを行って、全ての島の組の橋の長さを求める
+pub fn bridge_matrix<T, F>(grid: &Grid<T>, is_land: F) -> BridgeMatrix
+where
+    F: Fn(&T) -> bool,
+{
+    // 島を見つける（上下左右につながった陸地ごとにラベリング）
+    let islands: Vec<Vec<(usize, usize)>> = label_components(grid, &is_land, Connectivity::Four)
+        .islands
+        .into_iter()
+        .map(|island| island.cells)
+        .collect();
+
+    let k = islands.len();
+    let mut lengths = vec![vec![None; k]; k];
+    for i in 0..k {
+        lengths[i][i] = Some(0);
+        let sea = multi_source_bfs(grid, &islands[i], |_, cell| !is_land(cell));
+        for j in (i + 1)..k {
+            let length = best_bridge_to(grid, &sea, &islands[j]).map(|bridge| bridge.length);
+            lengths[i][j] = length;
+            lengths[j][i] = length;
+        }
+    }
+
+    BridgeMatrix { islands, lengths }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_map() -> Grid<char> {
+        Grid::parse(
+            "\
+##....###
+#.....#.#
+...##.###
+...##....
+.#.......
+.##..###.
+.....#.#.
+###..###.
+#.#......",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_shortest_bridge() {
+        let grid = sample_map();
+        let bridge = shortest_bridge(&grid, (0, 0), (3, 3), |&c| c == '#').unwrap();
+        assert_eq!(bridge.length, 3);
+        assert_eq!(bridge.cells.len(), 3);
+        assert!(bridge.cells.iter().all(|&cell| grid[cell] == '.'));
+        // 同じ島、海のマスを指定した場合
+        assert_eq!(
+            shortest_bridge(&grid, (0, 0), (1, 0), |&c| c == '#')
+                .unwrap()
+                .length,
+            0
+        );
+        assert_eq!(shortest_bridge(&grid, (0, 0), (0, 3), |&c| c == '#'), None);
+    }
+
+    #[test]
+    fn test_bridge_matrix_is_symmetric() {
+        let grid = sample_map();
+        let matrix = bridge_matrix(&grid, |&c| c == '#');
+        assert_eq!(matrix.islands.len(), 6);
+        for i in 0..6 {
+            for j in 0..6 {
+                assert_eq!(matrix.lengths[i][j], matrix.lengths[j][i]);
+                let direct =
+                    shortest_bridge(&grid, matrix.islands[i][0], matrix.islands[j][0], |&c| {
+                        c == '#'
+                    });
+                assert_eq!(matrix.lengths[i][j], direct.map(|b| b.length));
+            }
+        }
+    }
+
+    #[test]
+    fn test_unreachable_island() {
+        // 島1は別の島の輪の内側にあり、輪の外の島2へは海だけでは渡れない
+        let grid = Grid::parse("#####..\n#...#.#\n#.#.#..\n#...#..\n#####..").unwrap();
+        assert_eq!(shortest_bridge(&grid, (2, 2), (1, 6), |&c| c == '#'), None);
+        assert_eq!(
+            shortest_bridge(&grid, (2, 2), (0, 0), |&c| c == '#')
+                .unwrap()
+                .length,
+            1
+        );
+    }
+}
@@ -0,0 +1,192 @@
+use std::fs;
+
+use super::grid::Grid;
+
+// =============================================================================
+// 🛤️ 経路の型と表示（テキスト / PPM画像）
+// =============================================================================
+//
+// 探索中に「どのマスから来たか（親）」を記録しておくと、
+// ゴールから親をたどってスタートまで戻ることで経路が分かります。
+//
+//   parent[ゴール] → parent[...] → ... → スタート（親なし）
+//
+// たどった順番を逆にすれば スタート → ゴール の経路になります。
+//
+// 経路は地図に重ねて表示できます。
+//   '*' = 経路, 'o' = 調べたけど経路ではないマス（visitedを渡したとき）
+// 外部クレートなしで見られる画像として、PPM形式でも書き出せます。
+
+// 経路: 合計コストと、通るマス（スタート → ゴールの順）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GridPath {
+    pub cost: u64,
+    pub cells: Vec<(usize, usize)>,
+}
+
+impl GridPath {
+    // 移動の回数（マスの数 - 1）
+    pub fn steps(&self) -> usize {
+        self.cells.len().saturating_sub(1)
+    }
+
+    pub fn contains(&self, cell: (usize, usize)) -> bool {
+        self.cells.contains(&cell)
+    }
+}
+
+// 親の記録からゴールまでの経路を組み立てる（スタート → ゴールの順）
+pub fn trace_path(
+    parent: &Grid<Option<(usize, usize)>>,
+    goal: (usize, usize),
+) -> Vec<(usize, usize)> {
+    let mut cells = vec![goal];
+    let mut current = goal;
+    while let Some(prev) = parent[current] {
+        cells.push(prev);
+        current = prev;
+    }
+    cells.reverse();
+    cells
+}
+
+pub const PATH_MARK: char = '*';
+pub const VISITED_MARK: char = 'o';
+
+// 地図に経路を重ねる
+// スタートとゴール（経路の両端）は 'S' や 'G' なら元の文字のまま残し、
+// ただの通路（'.'）なら他の経路のマスと同じく PATH_MARK で表示する
+// visited を渡すと、調べたマスのうち経路以外の通路（'.'）を VISITED_MARK で表示する
+pub fn render_path(maze: &Grid<char>, path: &GridPath, visited: Option<&Grid<bool>>) -> Grid<char> {
+    let mut rendered = maze.clone();
+    if let Some(visited) = visited {
+        for pos in maze.positions() {
+            if visited[pos] && maze[pos] == '.' {
+                rendered[pos] = VISITED_MARK;
+            }
+        }
+    }
+    let last = path.cells.len().saturating_sub(1);
+    for (i, &cell) in path.cells.iter().enumerate() {
+        let endpoint = i == 0 || i == last;
+        rendered[cell] = if endpoint && maze[cell] != '.' {
+            maze[cell]
+        } else {
+            PATH_MARK
+        };
+    }
+    rendered
+}
+
+// =============================================================================
+// PPM画像
+// =============================================================================
+//
+// PPM（P6）は「ヘッダ + 画素ごとのRGB 3バイト」だけの単純な画像形式です。
+//   P6
+//   幅 高さ
+//   255
+//   RGBRGBRGB...
+// 多くの画像ビューアで開けます。1マスを scale × scale 画素で描きます。
+
+// マスの色
+fn cell_color(ch: char) -> [u8; 3] {
+    match ch {
+        '#' => [40, 40, 40],
+        'S' => [60, 180, 75],
+        'G' => [0, 130, 200],
+        PATH_MARK => [230, 25, 75],
+        VISITED_MARK => [170, 210, 255],
+        _ => [255, 255, 255],
+    }
+}
+
+// 文字の地図をPPM（P6）のバイト列にする
+// render_path の結果を渡せば、経路と調べたマスに色が付く
+pub fn to_ppm(grid: &Grid<char>, scale: usize) -> Vec<u8> {
+    let scale = scale.max(1);
+    let (width, height) = (grid.cols() * scale, grid.rows() * scale);
+    let mut bytes = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+    bytes.reserve(width * height * 3);
+    for row in 0..grid.rows() {
+        let colors: Vec<[u8; 3]> = grid.row(row).iter().map(|&ch| cell_color(ch)).collect();
+        for _ in 0..scale {
+            for color in &colors {
+                for _ in 0..scale {
+                    bytes.extend_from_slice(color);
+                }
+            }
+        }
+    }
+    bytes
+}
+
+// PPMファイルに書き出す
+pub fn save_ppm(file: &str, grid: &Grid<char>, scale: usize) -> Result<(), String> {
+    fs::write(file, to_ppm(grid, scale))
+        .map_err(|e| format!("ファイルに書き込めません({}): {}", file, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trace_and_render() {
+        let maze = Grid::parse("S..\n##.\nG..").unwrap();
+        let mut parent = Grid::new(3, 3, None);
+        parent[(0, 1)] = Some((0, 0));
+        parent[(0, 2)] = Some((0, 1));
+        parent[(1, 2)] = Some((0, 2));
+        parent[(2, 2)] = Some((1, 2));
+        parent[(2, 1)] = Some((2, 2));
+        parent[(2, 0)] = Some((2, 1));
+        let path = GridPath {
+            cost: 6,
+            cells: trace_path(&parent, (2, 0)),
+        };
+        assert_eq!(path.steps(), 6);
+        assert_eq!(path.cells.first(), Some(&(0, 0)));
+        assert_eq!(
+            render_path(&maze, &path, None).to_string(),
+            "S**\n##*\nG**\n"
+        );
+
+        let mut visited = Grid::new(3, 3, true);
+        visited[(2, 1)] = false;
+        // ゴールが通路のマスでも、訪問済みではなく経路として描く
+        let short = GridPath {
+            cost: 2,
+            cells: vec![(0, 0), (0, 1), (0, 2)],
+        };
+        assert_eq!(
+            render_path(&maze, &short, Some(&visited)).to_string(),
+            "S**\n##o\nG.o\n"
+        );
+        let from_aisle = GridPath {
+            cost: 2,
+            cells: vec![(2, 2), (2, 1), (2, 0)],
+        };
+        assert_eq!(
+            render_path(&maze, &from_aisle, Some(&visited)).to_string(),
+            "Soo\n##o\nG**\n"
+        );
+    }
+
+    #[test]
+    fn test_ppm_size_and_colors() {
+        let grid = Grid::parse("S#\n*G").unwrap();
+        let bytes = to_ppm(&grid, 2);
+        let header = b"P6\n4 4\n255\n";
+        assert_eq!(&bytes[..header.len()], header);
+        assert_eq!(bytes.len(), header.len() + 4 * 4 * 3);
+        let pixel = |x: usize, y: usize| {
+            let i = header.len() + (y * 4 + x) * 3;
+            [bytes[i], bytes[i + 1], bytes[i + 2]]
+        };
+        assert_eq!(pixel(1, 1), cell_color('S'));
+        assert_eq!(pixel(2, 0), cell_color('#'));
+        assert_eq!(pixel(0, 3), cell_color('*'));
+        assert_eq!(pixel(3, 3), cell_color('G'));
+    }
+}
@@ -0,0 +1,243 @@
+use std::collections::HashSet;
+
+use super::bitset::BitGrid;
+use super::dfs::explore_island_dfs_on;
+use super::grid::Grid;
+use super::multi_source_bfs::multi_source_bfs;
+use super::topology::{Orthogonal, Topology};
+
+// =============================================================================
+// 🎨 グリッドを書き換える: 塗りつぶし・輪郭・膨張と収縮・領域拡張
+// =============================================================================
+//
+// これまでの探索は地図を「見る」だけでした。ここでは探索の結果を使って地図を「変える」。
+//
+// - flood_fill   … つながった同じ値の領域を別の値で塗る（お絵かきソフトのバケツ）
+// - trace_boundary … 島の外周のマスを、時計回りの順番で並べる
+// - dilate / erode … 陸地を radius マスぶん太らせる / 痩せさせる
+// - grow_region  … 数値の地図で、種のマスと値が近いマスを広げていく
+//
+// 塗りつぶしはDFS（explore_island_dfs_on）、膨張・収縮と領域拡張は多始点BFSの上に作ります。
+
+// startとつながった、startと同じ値の領域を replacement で塗る
+// 塗ったマスの数を返す（同じ値で塗るときは何もしない）
+pub fn flood_fill<T, Topo>(
+    grid: &mut Grid<T>,
+    topology: &Topo,
+    start: (usize, usize),
+    replacement: T,
+) -> usize
+where
+    T: Clone + PartialEq,
+    Topo: Topology + ?Sized,
+{
+    let original = grid[start].clone();
+    if original == replacement {
+        return 0;
+    }
+    let mut visited = BitGrid::new(grid.rows(), grid.cols());
+    let region = explore_island_dfs_on(grid, topology, &mut visited, start, |value| {
+        *value == original
+    });
+    for &cell in &region {
+        grid[cell] = replacement.clone();
+    }
+    region.len()
+}
+
+// 8方向を時計回りに（左から始める）
+const CLOCKWISE: [(isize, isize); 8] = [
+    (0, -1),
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+];
+
+// startを含む島（上下左右につながった陸地）の外周を、時計回りの順に返す
+// 一番上の行の一番左のマスから始まる（ムーア近傍の輪郭追跡）
+// startが陸地でなければ空
+pub fn trace_boundary<T, F>(
+    grid: &Grid<T>,
+    start: (usize, usize),
+    is_land: F,
+) -> Vec<(usize, usize)>
+where
+    F: Fn(&T) -> bool,
+{
+    if !is_land(&grid[start]) {
+        return Vec::new();
+    }
+    // 島のマスだけを陸地とみなす（斜めに接した別の島に迷い込まないように）
+    let island: HashSet<(usize, usize)> = explore_island_dfs_on(
+        grid,
+        &Orthogonal,
+        &mut BitGrid::new(grid.rows(), grid.cols()),
+        start,
+        &is_land,
+    )
+    .into_iter()
+    .collect();
+    let inside =
+        |(r, c): (isize, isize)| r >= 0 && c >= 0 && island.contains(&(r as usize, c as usize));
+    let first = island.iter().copied().min().unwrap_or(start);
+    let first = (first.0 as isize, first.1 as isize);
+
+    // current の周りを、back（直前に調べた海のマスの方向）の次から時計回りに見て、
+    // 最初に見つかった島のマスへ進む
+    let step = |current: (isize, isize), back: usize| {
+        (1..=8).map(|k| (back + k) % 8).find_map(|d| {
+            let next = (current.0 + CLOCKWISE[d].0, current.1 + CLOCKWISE[d].1);
+            if !inside(next) {
+                return None;
+            }
+            // 次のマスから見た「直前に調べた海のマス」の方向
+            let (dr, dc) = CLOCKWISE[(d + 7) % 8];
+            let water = (current.0 + dr - next.0, current.1 + dc - next.1);
+            let back = CLOCKWISE
+                .iter()
+                .position(|&offset| offset == water)
+                .unwrap_or(0);
+            Some((next, back))
+        })
+    };
+
+    // 一番上の行の一番左のマスなので、左は必ず海
+    let Some(second) = step(first, 0) else {
+        // 1マスだけの島
+        return vec![(first.0 as usize, first.1 as usize)];
+    };
+    let mut outline = vec![first];
+    let mut state = second;
+    // 最初と同じ「first から second へ進む」動きが出たら一周した
+    while let Some(next) = step(state.0, state.1) {
+        if state.0 == first && next.0 == second.0 {
+            break;
+        }
+        outline.push(state.0);
+        state = next;
+    }
+    outline
+        .into_iter()
+        .map(|(r, c)| (r as usize, c as usize))
+        .collect()
+}
+
+// 陸地を radius マス太らせる（陸地から上下左右の歩数で radius 以内のマスを陸地にする）
+pub fn dilate(land: &Grid<bool>, radius: usize) -> Grid<bool> {
+    let sources = land.find_all(|&l| l);
+    let distance = multi_source_bfs(land, &sources, |_, _| true).distance;
+    distance.map(|d| d.is_some_and(|d| d <= radius))
+}
+
+// 陸地を radius マス痩せさせる（海か地図の外から radius 以内の陸地を海にする）
+pub fn erode(land: &Grid<bool>, radius: usize) -> Grid<bool> {
+    let sources = land.find_all(|&l| !l);
+    let distance = multi_source_bfs(land, &sources, |_, _| true).distance;
+    let (rows, cols) = (land.rows(), land.cols());
+    Grid::from_fn(rows, cols, |row, col| {
+        // 地図の外までの歩数（外は海とみなす）
+        let to_outside = (row + 1).min(col + 1).min(rows - row).min(cols - col);
+        let to_water = distance[(row, col)].map_or(to_outside, |d| d.min(to_outside));
+        land[(row, col)] && to_water > radius
+    })
+}
+
+// seedとつながっていて、seedの値との差が tolerance 以下のマスを集める（BFSの順）
+pub fn grow_region<T>(grid: &Grid<T>, seed: (usize, usize), tolerance: f64) -> Vec<(usize, usize)>
+where
+    T: Copy + Into<f64>,
+{
+    let base: f64 = grid[seed].into();
+    let result = multi_source_bfs(grid, &[seed], |_, &value| {
+        (value.into() - base).abs() <= tolerance
+    });
+    let mut cells: Vec<(usize, usize)> = grid
+        .positions()
+        .filter(|&pos| result.distance[pos].is_some())
+        .collect();
+    cells.sort_by_key(|&pos| result.distance[pos]);
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dfs_bfs_practice::topology::{CornerRule, Diagonal};
+
+    #[test]
+    fn test_flood_fill() {
+        let mut grid = Grid::parse("..#..\n.#.#.\n#...#").unwrap();
+        // 左上の '.' は上下左右では3マスだけ
+        assert_eq!(flood_fill(&mut grid, &Orthogonal, (0, 0), 'o'), 3);
+        assert_eq!(grid.to_string(), "oo#..\no#.#.\n#...#\n");
+        // 斜めにもつながるなら、'#' の隙間を抜けて広がる
+        assert_eq!(
+            flood_fill(&mut grid, &Diagonal(CornerRule::Allow), (1, 2), 'x'),
+            7
+        );
+        assert_eq!(grid.to_string(), "oo#xx\no#x#x\n#xxx#\n");
+        assert_eq!(flood_fill(&mut grid, &Orthogonal, (0, 0), 'o'), 0);
+    }
+
+    #[test]
+    fn test_trace_boundary() {
+        let grid = Grid::parse("....\n.###\n.###\n.###").unwrap();
+        let outline = trace_boundary(&grid, (2, 2), |&c| c == '#');
+        assert_eq!(
+            outline,
+            vec![
+                (1, 1),
+                (1, 2),
+                (1, 3),
+                (2, 3),
+                (3, 3),
+                (3, 2),
+                (3, 1),
+                (2, 1)
+            ]
+        );
+
+        // L字の島。斜めに接している別の島 (0, 2) には入らない
+        let grid = Grid::parse("#.#\n#..\n##.").unwrap();
+        let outline = trace_boundary(&grid, (2, 1), |&c| c == '#');
+        assert_eq!(outline, vec![(0, 0), (1, 0), (2, 1), (2, 0), (1, 0)]);
+        assert_eq!(trace_boundary(&grid, (0, 2), |&c| c == '#'), vec![(0, 2)]);
+        assert!(trace_boundary(&grid, (0, 1), |&c| c == '#').is_empty());
+    }
+
+    #[test]
+    fn test_dilate_and_erode() {
+        let land = Grid::parse(".....\n.....\n..#..\n.....\n.....")
+            .unwrap()
+            .map(|&c| c == '#');
+        let grown = dilate(&land, 1);
+        assert_eq!(
+            grown.map(|&l| if l { '#' } else { '.' }).to_string(),
+            ".....\n..#..\n.###.\n..#..\n.....\n"
+        );
+        assert_eq!(erode(&grown, 1), land);
+        // 地図の端は海とみなすので、全部陸地でも端から痩せる
+        let solid = Grid::new(3, 4, true);
+        assert_eq!(erode(&solid, 1).find_all(|&l| l), vec![(1, 1), (1, 2)]);
+        assert!(erode(&solid, 2).cells().iter().all(|&l| !l));
+    }
+
+    #[test]
+    fn test_grow_region_with_tolerance() {
+        let heights: Grid<i32> = Grid::from_rows(vec![
+            vec![10, 11, 30, 12],
+            vec![12, 13, 31, 11],
+            vec![40, 12, 12, 10],
+        ])
+        .unwrap();
+        let region = grow_region(&heights, (0, 0), 3.0);
+        assert_eq!(region.first(), Some(&(0, 0)));
+        assert_eq!(region.len(), 9);
+        assert!(!region.contains(&(0, 2)) && !region.contains(&(2, 0)));
+        assert_eq!(grow_region(&heights, (0, 0), 0.5), vec![(0, 0)]);
+    }
+}
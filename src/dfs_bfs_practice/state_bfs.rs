@@ -0,0 +1,255 @@
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+use super::grid::Grid;
+
+// =============================================================================
+// 🔑 状態空間のBFS: 鍵と扉のある迷路
+// =============================================================================
+//
+// これまでのBFSは「状態 = 今いるマス (row, col)」でした。
+// 鍵（小文字 a-z）と扉（大文字 A-Z）がある迷路では、
+// 同じマスにいても「どの鍵を持っているか」で次に行ける場所が変わります。
+//
+// そこで状態を (マス, 持っている鍵) にしてBFSします。
+// 鍵の集合はビットマスクで表します。
+//   鍵a → 1 << 0, 鍵b → 1 << 1, ... 鍵z → 1 << 25
+//   keys & (1 << 1) != 0 なら鍵bを持っている
+//
+// 訪問済みは「状態全体」で管理します（マスだけで管理すると、
+// 鍵を取って戻ってくる経路が「訪問済み」で止められてしまう）。
+//
+// スイッチ '!' を踏むと、ゲート '|' の開閉が切り替わります（最初は閉じている）。
+// これも状態に1ビットのフラグとして持たせます。
+//
+//   S.a#G        鍵aを取ってから扉Aを通る
+//   .#.#.        状態の数 = マスの数 × 2^(鍵の数) × 2（スイッチ）
+//   ...A.
+
+// 状態空間のBFS（状態の型は自由）
+// next(状態) = 1手で移れる状態の一覧、is_goal(状態) = ゴールか
+// スタートからゴールまでの状態の列を返す（到達できなければNone）
+pub fn state_bfs<S, N, I, G>(start: S, mut next: N, is_goal: G) -> Option<Vec<S>>
+where
+    S: Clone + Eq + Hash,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+    G: Fn(&S) -> bool,
+{
+    // parent[状態] = その状態の直前の状態（訪問済みの記録も兼ねる）
+    let mut parent: HashMap<S, Option<S>> = HashMap::new();
+    let mut queue = VecDeque::new();
+    parent.insert(start.clone(), None);
+    queue.push_back(start);
+
+    while let Some(current) = queue.pop_front() {
+        if is_goal(&current) {
+            // 親をたどって状態の列を作る
+            let mut states = vec![current.clone()];
+            let mut state = current;
+            while let Some(Some(prev)) = parent.get(&state) {
+                states.push(prev.clone());
+                state = prev.clone();
+            }
+            states.reverse();
+            return Some(states);
+        }
+        for state in next(&current) {
+            if !parent.contains_key(&state) {
+                parent.insert(state.clone(), Some(current.clone()));
+                queue.push_back(state);
+            }
+        }
+    }
+
+    None
+}
+
+// 鍵と扉の迷路の状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyState {
+    pub pos: (usize, usize),
+    // 持っている鍵のビットマスク
+    pub keys: u32,
+    // スイッチでゲートが開いているか
+    pub gates_open: bool,
+}
+
+impl KeyState {
+    pub fn has_key(&self, key: char) -> bool {
+        self.keys & key_bit(key) != 0
+    }
+}
+
+// 鍵の文字（a-z）に対応するビット
+pub fn key_bit(key: char) -> u32 {
+    1 << (key.to_ascii_lowercase() as u32 - 'a' as u32)
+}
+
+// 迷路にある全ての鍵のビットマスク
+pub fn all_keys(maze: &Grid<char>) -> u32 {
+    maze.cells()
+        .iter()
+        .filter(|c| c.is_ascii_lowercase())
+        .fold(0, |keys, &c| keys | key_bit(c))
+}
+
+// 鍵と扉の迷路の解
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyMazeSolution {
+    // スタートからゴールまでの状態の列
+    pub states: Vec<KeyState>,
+}
+
+impl KeyMazeSolution {
+    pub fn steps(&self) -> usize {
+        self.states.len() - 1
+    }
+
+    pub fn cells(&self) -> Vec<(usize, usize)> {
+        self.states.iter().map(|state| state.pos).collect()
+    }
+
+    // 鍵を拾った順番
+    pub fn key_order(&self) -> Vec<char> {
+        self.states
+            .windows(2)
+            .filter(|pair| pair[0].keys != pair[1].keys)
+            .map(|pair| {
+                let bit = pair[1].keys & !pair[0].keys;
+                (b'a' + bit.trailing_zeros() as u8) as char
+            })
+            .collect()
+    }
+}
+
+// 鍵と扉の迷路を解く
+// '#' = 壁, 'S' = スタート, 'G' = ゴール, 'a'-'z' = 鍵, 'A'-'Z' = 扉（対応する鍵が必要、S/Gを除く）
+// '!' = スイッチ（踏むとゲートの開閉が切り替わる）, '|' = ゲート
+// required_keys の鍵を全て持ってゴールに着く最短の手順を求める
+pub fn solve_key_maze(
+    maze: &Grid<char>,
+    required_keys: u32,
+) -> Result<Option<KeyMazeSolution>, String> {
+    let start = maze
+        .find(|&c| c == 'S')
+        .ok_or("迷路にスタート 'S' がありません")?;
+    let goal = maze
+        .find(|&c| c == 'G')
+        .ok_or("迷路にゴール 'G' がありません")?;
+    if let Some((row, col)) = maze.find(|&c| !is_maze_char(c)) {
+        let ch = maze[(row, col)];
+        return Err(format!(
+            "{}行目{}文字目: 迷路に使えない文字 '{}' です",
+            row + 1,
+            col + 1,
+            ch
+        ));
+    }
+
+    let initial = KeyState {
+        pos: start,
+        keys: 0,
+        gates_open: false,
+    };
+    let states = state_bfs(
+        initial,
+        |state: &KeyState| {
+            let state = *state;
+            maze.neighbors4(state.pos).filter_map(move |next| {
+                let cell = maze[next];
+                let passable = match cell {
+                    '#' => false,
+                    'S' | 'G' => true,
+                    '|' => state.gates_open,
+                    'A'..='Z' => state.has_key(cell),
+                    _ => true,
+                };
+                if !passable {
+                    return None;
+                }
+                let mut moved = KeyState { pos: next, ..state };
+                if cell.is_ascii_lowercase() {
+                    moved.keys |= key_bit(cell);
+                }
+                if cell == '!' {
+                    moved.gates_open = !moved.gates_open;
+                }
+                Some(moved)
+            })
+        },
+        |state| state.pos == goal && state.keys & required_keys == required_keys,
+    );
+
+    Ok(states.map(|states| KeyMazeSolution { states }))
+}
+
+fn is_maze_char(c: char) -> bool {
+    matches!(c, '.' | '#' | 'S' | 'G' | '!' | '|') || c.is_ascii_alphabetic()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_door_needs_key() {
+        let maze = Grid::parse(
+            "\
+S.a#G
+.#.#.
+...A.",
+        )
+        .unwrap();
+        let solution = solve_key_maze(&maze, 0).unwrap().unwrap();
+        // S → a（2手）→ 下へ回って扉A → G
+        assert_eq!(solution.steps(), 8);
+        assert_eq!(solution.key_order(), vec!['a']);
+        assert_eq!(solution.cells().last(), Some(&(0, 4)));
+
+        // 鍵がなければ扉は通れない
+        let locked = Grid::parse("S.#G\n..A.").unwrap();
+        assert_eq!(solve_key_maze(&locked, 0).unwrap(), None);
+        assert!(solve_key_maze(&Grid::parse("S.?G").unwrap(), 0).is_err());
+    }
+
+    #[test]
+    fn test_required_keys_and_visited_state() {
+        // 鍵bは寄り道だが、全ての鍵が必要なら取りに行く
+        // 同じマスを鍵の有無の違う状態で2回通る
+        let maze = Grid::parse(
+            "\
+b..S..G",
+        )
+        .unwrap();
+        let direct = solve_key_maze(&maze, 0).unwrap().unwrap();
+        assert_eq!(direct.steps(), 3);
+        let all = solve_key_maze(&maze, all_keys(&maze)).unwrap().unwrap();
+        assert_eq!(all.steps(), 3 + 6);
+        assert_eq!(all.cells().iter().filter(|&&c| c == (0, 3)).count(), 2);
+    }
+
+    #[test]
+    fn test_switch_opens_gate() {
+        let maze = Grid::parse(
+            "\
+S.|.G
+!....
+#####",
+        )
+        .unwrap();
+        // 下の道を回ればゲートを通らずに済む（6手）
+        let solution = solve_key_maze(&maze, 0).unwrap().unwrap();
+        assert_eq!(solution.steps(), 6);
+        let walled = Grid::parse("S.|.G\n!.###").unwrap();
+        let solution = solve_key_maze(&walled, 0).unwrap().unwrap();
+        // スイッチを踏んでからゲートを通る
+        assert!(solution.states.iter().any(|s| s.gates_open));
+        assert_eq!(solution.steps(), 6);
+        // スイッチがなければゲートは開かない
+        assert_eq!(
+            solve_key_maze(&Grid::parse("S.|.G").unwrap(), 0).unwrap(),
+            None
+        );
+    }
+}
@@ -0,0 +1,273 @@
+use std::io::BufRead;
+
+use super::labeling::BoundingBox;
+use crate::union_find::UnionFind;
+
+// =============================================================================
+// 📜 1行ずつ読みながら島を数える（地図全体をメモリに載せない）
+// =============================================================================
+//
+// 衛星画像のマスクのように、地図全体がメモリに入らないこともあります。
+// 島（上下左右につながった陸地）は、上の行と今の行だけを見れば
+// つながりが分かるので、1行ずつ読み進めながら数えられます。
+//
+// 覚えておくのは次の2つだけです（どちらも列の数に比例する大きさ）。
+// - 1つ前の行の各マスのラベル（どの島のかけらか）
+// - ラベルどうしのつながりを管理する Union-Find
+//
+//   前の行:  0 0 . 1 1        今の行で 0 と 1 がつながった
+//   今の行:  # # # # .        → Union-Find で 0 と 1 を同じ島にする
+//
+// 行を読み終えたら、今の行に1マスも続いていない島は「もう大きくならない」ので
+// 完成した島として報告し、忘れます。残った島のラベルは 0, 1, 2, ... に詰め直します。
+
+// 完成した島
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamedIsland {
+    pub area: usize,
+    pub bounding_box: BoundingBox,
+}
+
+impl StreamedIsland {
+    fn single(row: usize, col: usize) -> StreamedIsland {
+        StreamedIsland {
+            area: 1,
+            bounding_box: BoundingBox {
+                min_row: row,
+                min_col: col,
+                max_row: row,
+                max_col: col,
+            },
+        }
+    }
+
+    // 2つのかけらを1つの島にまとめる
+    fn merge(&mut self, other: &StreamedIsland) {
+        self.area += other.area;
+        let (a, b) = (&mut self.bounding_box, other.bounding_box);
+        a.min_row = a.min_row.min(b.min_row);
+        a.min_col = a.min_col.min(b.min_col);
+        a.max_row = a.max_row.max(b.max_row);
+        a.max_col = a.max_col.max(b.max_col);
+    }
+}
+
+// 読み終えたときのまとめ
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamSummary {
+    pub rows: usize,
+    pub cols: usize,
+    pub count: usize,
+    pub largest: usize,
+}
+
+// readerから1行ずつ読み、is_land(文字) が true のマスを陸地として島を数える
+// 島が完成するたびに on_island を呼ぶ（完成した順なので、地図の上の方の島から届く）
+// 空行は読み飛ばす。行の長さが揃っていなければエラー
+pub fn stream_islands<R, F, E>(
+    reader: R,
+    is_land: F,
+    mut on_island: E,
+) -> Result<StreamSummary, String>
+where
+    R: BufRead,
+    F: Fn(char) -> bool,
+    E: FnMut(StreamedIsland),
+{
+    let mut summary = StreamSummary {
+        rows: 0,
+        cols: 0,
+        count: 0,
+        largest: 0,
+    };
+    // 前の行のラベルと、ラベルごとのかけら（ラベルは 0..partial.len() に詰めてある）
+    let mut previous: Vec<Option<usize>> = Vec::new();
+    let mut partial: Vec<StreamedIsland> = Vec::new();
+    let mut emit = |island: StreamedIsland, summary: &mut StreamSummary| {
+        summary.count += 1;
+        summary.largest = summary.largest.max(island.area);
+        on_island(island);
+    };
+
+    for (line_number, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| format!("{}行目を読めません: {}", line_number + 1, e))?;
+        let line = line.trim_end_matches('\r');
+        if line.is_empty() {
+            continue;
+        }
+        let row = summary.rows;
+        let cells: Vec<bool> = line.chars().map(&is_land).collect();
+        if row == 0 {
+            summary.cols = cells.len();
+            previous = vec![None; cells.len()];
+        } else if cells.len() != summary.cols {
+            return Err(format!(
+                "{}行目の長さが{}です（最初の行は{}）",
+                line_number + 1,
+                cells.len(),
+                summary.cols
+            ));
+        }
+
+        // 前の行のかけらは 0..partial.len()、この行で新しくできたかけらはその後ろ
+        let mut groups = UnionFind::new(partial.len());
+        let mut current: Vec<Option<usize>> = vec![None; cells.len()];
+        for (col, &land) in cells.iter().enumerate() {
+            if !land {
+                continue;
+            }
+            let left = if col > 0 { current[col - 1] } else { None };
+            let label = match (left, previous[col]) {
+                (Some(a), Some(b)) => {
+                    groups.union(a, b);
+                    a
+                }
+                (Some(a), None) | (None, Some(a)) => a,
+                (None, None) => {
+                    partial.push(StreamedIsland::single(row, col));
+                    current[col] = Some(groups.push());
+                    continue;
+                }
+            };
+            partial[label].merge(&StreamedIsland::single(row, col));
+            current[col] = Some(label);
+        }
+
+        // かけらを Union-Find の代表にまとめる
+        let mut merged: Vec<Option<StreamedIsland>> = vec![None; partial.len()];
+        for (label, piece) in partial.iter().enumerate() {
+            let root = groups.find(label);
+            match &mut merged[root] {
+                Some(island) => island.merge(piece),
+                slot => *slot = Some(*piece),
+            }
+        }
+
+        // この行に続いている島にだけ新しいラベルを振る
+        let mut relabel: Vec<Option<usize>> = vec![None; partial.len()];
+        let mut next_partial = Vec::new();
+        for label in current.iter_mut().flatten() {
+            let root = groups.find(*label);
+            *label = match relabel[root] {
+                Some(new_label) => new_label,
+                None => {
+                    let new_label = next_partial.len();
+                    next_partial.extend(merged[root].take());
+                    relabel[root] = Some(new_label);
+                    new_label
+                }
+            };
+        }
+        // 残った島はもう大きくならないので完成
+        for island in merged.into_iter().flatten() {
+            emit(island, &mut summary);
+        }
+
+        previous = current;
+        partial = next_partial;
+        summary.rows += 1;
+    }
+
+    // 最後の行まで続いていた島も完成
+    for island in partial {
+        emit(island, &mut summary);
+    }
+    Ok(summary)
+}
+
+// 全ての島を集める（左上の島から順に並べる）
+pub fn collect_islands_streaming<R, F>(
+    reader: R,
+    is_land: F,
+) -> Result<(StreamSummary, Vec<StreamedIsland>), String>
+where
+    R: BufRead,
+    F: Fn(char) -> bool,
+{
+    let mut islands = Vec::new();
+    let summary = stream_islands(reader, is_land, |island| islands.push(island))?;
+    islands.sort_by_key(|island| (island.bounding_box.min_row, island.bounding_box.min_col));
+    Ok((summary, islands))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dfs_bfs_practice::grid::Grid;
+    use crate::dfs_bfs_practice::labeling::{label_components, Connectivity};
+    use crate::rng::XorShift64;
+
+    #[test]
+    fn test_u_shape_merges_late() {
+        // 2本の柱は最後の行でつながる。途中で別の島として報告してはいけない
+        let text = "#.#.#\n#.#.#\n###.#\n\n";
+        let (summary, islands) = collect_islands_streaming(text.as_bytes(), |c| c == '#').unwrap();
+        assert_eq!(
+            (summary.rows, summary.cols, summary.count, summary.largest),
+            (3, 5, 2, 7)
+        );
+        assert_eq!(islands[0].area, 7);
+        assert_eq!(
+            islands[0].bounding_box,
+            BoundingBox {
+                min_row: 0,
+                min_col: 0,
+                max_row: 2,
+                max_col: 2
+            }
+        );
+        assert_eq!(
+            islands[1].bounding_box,
+            BoundingBox {
+                min_row: 0,
+                min_col: 4,
+                max_row: 2,
+                max_col: 4
+            }
+        );
+
+        assert!(collect_islands_streaming("##\n###".as_bytes(), |c| c == '#').is_err());
+        let empty = collect_islands_streaming("".as_bytes(), |c| c == '#').unwrap();
+        assert_eq!(empty.0.count, 0);
+    }
+
+    #[test]
+    fn test_matches_labeling() {
+        let mut rng = XorShift64::new(21);
+        for &(rows, cols, density) in &[
+            (1, 9, 0.5),
+            (9, 1, 0.5),
+            (20, 30, 0.5),
+            (35, 17, 0.6),
+            (25, 25, 0.7),
+        ] {
+            let grid = Grid::from_fn(
+                rows,
+                cols,
+                |_, _| if rng.chance(density) { '#' } else { '.' },
+            );
+            let text = grid.to_string();
+            let (summary, islands) =
+                collect_islands_streaming(text.as_bytes(), |c| c == '#').unwrap();
+
+            let labeling = label_components(&grid, |&c| c == '#', Connectivity::Four);
+            let mut expected: Vec<(BoundingBox, usize)> = labeling
+                .islands
+                .iter()
+                .map(|island| (island.bounding_box, island.area))
+                .collect();
+            let mut actual: Vec<(BoundingBox, usize)> = islands
+                .iter()
+                .map(|island| (island.bounding_box, island.area))
+                .collect();
+            let key = |&(b, area): &(BoundingBox, usize)| {
+                (b.min_row, b.min_col, b.max_row, b.max_col, area)
+            };
+            expected.sort_by_key(key);
+            actual.sort_by_key(key);
+            assert_eq!(actual, expected, "{}×{}", rows, cols);
+            assert_eq!(summary.count, labeling.count());
+            assert_eq!(summary.largest, labeling.max_area());
+        }
+    }
+}
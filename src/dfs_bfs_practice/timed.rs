@@ -0,0 +1,364 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+
+use super::grid::Grid;
+
+// =============================================================================
+// ⏱️ 時間つきのBFS / A*: 動く障害物を避ける
+// =============================================================================
+//
+// 見回りのロボットが1手（1tick）ごとに動く床では、
+// 「今は通れないマスでも、少し待てば通れる」ことがあります。
+//
+// そこで状態を (マス, 時刻) にして探索します。1手でできることは
+//   - 上下左右に1マス動く
+//   - その場で待つ
+// のどちらかで、どちらも時刻が1進みます。
+//
+// ぶつかるのは次の2つです。
+//   - 移動先のマスに、次の時刻に障害物がいる
+//   - 障害物とすれ違う（自分が a → b、障害物が b → a と入れ替わる）
+//
+// 時刻は無限に進むので、そのままでは状態が無限にあります。
+//   - 周期的な見回り: 時刻を周期で割った余りが同じなら同じ状態
+//   - 時刻ごとの指定: 最後の時刻より後は障害物が止まっているので同じ状態
+// とまとめることで、探索は必ず終わります。
+
+// 周期的な見回り（ObstacleSchedule::periodic で作る）
+// ルートが空でないことと周期を作るときに確かめるので、中身は直接いじれないようにしておく
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Patrols {
+    // 見回りのルート: 時刻tには route[t % route.len()] にいる
+    routes: Vec<Vec<(usize, usize)>>,
+    // 全ての見回りが元の位置に戻るまでの時間（ルートの長さの最小公倍数）
+    period: usize,
+}
+
+impl Patrols {
+    pub fn routes(&self) -> &[Vec<(usize, usize)>] {
+        &self.routes
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+}
+
+// 障害物の予定
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ObstacleSchedule {
+    // 周期的な見回り
+    Periodic(Patrols),
+    // 時刻ごとに埋まっているマス: occupied[t]（最後の時刻より後は最後のまま）
+    Explicit(Vec<Vec<(usize, usize)>>),
+}
+
+impl ObstacleSchedule {
+    // 周期的な見回りの予定を作る
+    // 空のルートと、周期（ルートの長さの最小公倍数）が usize に収まらないときはエラー
+    pub fn periodic(routes: Vec<Vec<(usize, usize)>>) -> Result<ObstacleSchedule, String> {
+        if let Some(i) = routes.iter().position(|route| route.is_empty()) {
+            return Err(format!("{}番目の見回りのルートが空です", i + 1));
+        }
+        let mut period: usize = 1;
+        for route in &routes {
+            period = (period / gcd(period, route.len()))
+                .checked_mul(route.len())
+                .ok_or_else(|| "見回りの周期が大きすぎます".to_string())?;
+        }
+        Ok(ObstacleSchedule::Periodic(Patrols { routes, period }))
+    }
+
+    // 時刻tに cell が埋まっているか
+    pub fn is_occupied(&self, cell: (usize, usize), t: usize) -> bool {
+        match self {
+            ObstacleSchedule::Periodic(patrols) => patrols
+                .routes
+                .iter()
+                .any(|route| route[t % route.len()] == cell),
+            ObstacleSchedule::Explicit(occupied) => match occupied.len() {
+                0 => false,
+                len => occupied[t.min(len - 1)].contains(&cell),
+            },
+        }
+    }
+
+    // 時刻tに from → to へ動くとぶつかるか（移動先が埋まる / すれ違う）
+    pub fn blocks_move(&self, from: (usize, usize), to: (usize, usize), t: usize) -> bool {
+        if self.is_occupied(to, t + 1) {
+            return true;
+        }
+        if from == to {
+            return false;
+        }
+        match self {
+            // 同じ見回りロボットが to → from と動くなら、すれ違いになる
+            ObstacleSchedule::Periodic(patrols) => patrols
+                .routes
+                .iter()
+                .any(|route| route[t % route.len()] == to && route[(t + 1) % route.len()] == from),
+            // どの障害物か分からないので、to にいたものが from に来るならすれ違いとみなす
+            ObstacleSchedule::Explicit(_) => {
+                self.is_occupied(to, t) && self.is_occupied(from, t + 1)
+            }
+        }
+    }
+
+    // 同じ状態とみなせる時刻の番号
+    fn time_key(&self, t: usize) -> usize {
+        match self {
+            ObstacleSchedule::Periodic(patrols) => t % patrols.period,
+            ObstacleSchedule::Explicit(occupied) => t.min(occupied.len().saturating_sub(1)),
+        }
+    }
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+// 行って戻る見回りのルート（[a, b, c] → [a, b, c, b]）
+pub fn ping_pong(cells: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let mut route = cells.to_vec();
+    if cells.len() > 2 {
+        route.extend(cells[1..cells.len() - 1].iter().rev());
+    }
+    route
+}
+
+// 時刻つきの経路
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimedPath {
+    // (マス, 時刻) の列（時刻0のスタートから1ずつ増える）
+    pub steps: Vec<((usize, usize), usize)>,
+}
+
+impl TimedPath {
+    // ゴールに着いた時刻
+    pub fn arrival_time(&self) -> usize {
+        self.steps.last().map_or(0, |&(_, t)| t)
+    }
+
+    // その場で待った回数
+    pub fn waits(&self) -> usize {
+        self.steps
+            .windows(2)
+            .filter(|pair| pair[0].0 == pair[1].0)
+            .count()
+    }
+}
+
+// 探索の状態の鍵: (マス, 時刻の番号)
+type StateKey = ((usize, usize), usize);
+
+// 1手で移れるマス（その場で待つ + 上下左右）
+fn timed_moves<'a, T, F>(
+    grid: &'a Grid<T>,
+    schedule: &'a ObstacleSchedule,
+    passable: &'a F,
+    cell: (usize, usize),
+    t: usize,
+) -> impl Iterator<Item = (usize, usize)> + 'a
+where
+    F: Fn(&T) -> bool,
+{
+    std::iter::once(cell)
+        .chain(grid.neighbors4(cell))
+        .filter(move |&next| passable(&grid[next]) && !schedule.blocks_move(cell, next, t))
+}
+
+// 親の記録から時刻つきの経路を組み立てる
+fn trace_timed(
+    schedule: &ObstacleSchedule,
+    parent: &HashMap<StateKey, (usize, usize)>,
+    goal: (usize, usize),
+    arrival: usize,
+) -> TimedPath {
+    let mut steps = vec![(goal, arrival)];
+    let (mut cell, mut t) = (goal, arrival);
+    while t > 0 {
+        cell = parent[&(cell, schedule.time_key(t))];
+        t -= 1;
+        steps.push((cell, t));
+    }
+    steps.reverse();
+    TimedPath { steps }
+}
+
+// 時間つきのBFS: 一番早くゴールに着く経路を求める
+// 時刻0にスタートが埋まっている場合や、どうしても着けない場合はNone
+pub fn timed_bfs<T, F>(
+    grid: &Grid<T>,
+    start: (usize, usize),
+    goal: (usize, usize),
+    schedule: &ObstacleSchedule,
+    passable: F,
+) -> Option<TimedPath>
+where
+    F: Fn(&T) -> bool,
+{
+    if schedule.is_occupied(start, 0) {
+        return None;
+    }
+    // parent[(マス, 時刻の番号)] = 1つ前の時刻にいたマス
+    // 訪問済みは (マス, 時刻の番号) で管理する（同じマスでも時刻が違えば別の状態）
+    let mut parent = HashMap::new();
+    let mut seen = HashSet::new();
+    let mut queue = VecDeque::new();
+    seen.insert((start, schedule.time_key(0)));
+    queue.push_back((start, 0));
+
+    while let Some((cell, t)) = queue.pop_front() {
+        if cell == goal {
+            return Some(trace_timed(schedule, &parent, goal, t));
+        }
+        for next in timed_moves(grid, schedule, &passable, cell, t) {
+            let key = (next, schedule.time_key(t + 1));
+            if seen.insert(key) {
+                parent.insert(key, cell);
+                queue.push_back((next, t + 1));
+            }
+        }
+    }
+
+    None
+}
+
+// 時間つきのA*: ゴールまでのマンハッタン距離を見積もりにする
+// 結果の到着時刻は timed_bfs と同じで、調べる状態が少なくて済む
+pub fn timed_astar<T, F>(
+    grid: &Grid<T>,
+    start: (usize, usize),
+    goal: (usize, usize),
+    schedule: &ObstacleSchedule,
+    passable: F,
+) -> Option<TimedPath>
+where
+    F: Fn(&T) -> bool,
+{
+    if schedule.is_occupied(start, 0) {
+        return None;
+    }
+    let estimate = |cell: (usize, usize)| cell.0.abs_diff(goal.0) + cell.1.abs_diff(goal.1);
+    let mut parent = HashMap::new();
+    // best[(マス, 時刻の番号)] = そこに着いた一番早い時刻
+    let mut best: HashMap<StateKey, usize> = HashMap::new();
+    let mut heap = BinaryHeap::new();
+    best.insert((start, schedule.time_key(0)), 0);
+    heap.push(Reverse((estimate(start), 0, start)));
+
+    while let Some(Reverse((_, t, cell))) = heap.pop() {
+        if best.get(&(cell, schedule.time_key(t))) != Some(&t) {
+            continue;
+        }
+        if cell == goal {
+            return Some(trace_timed(schedule, &parent, goal, t));
+        }
+        for next in timed_moves(grid, schedule, &passable, cell, t) {
+            let key = (next, schedule.time_key(t + 1));
+            if best.get(&key).is_none_or(|&known| t + 1 < known) {
+                best.insert(key, t + 1);
+                parent.insert(key, cell);
+                heap.push(Reverse((t + 1 + estimate(next), t + 1, next)));
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 経路が障害物とぶつからないか
+    fn assert_collision_free(schedule: &ObstacleSchedule, path: &TimedPath) {
+        assert!(!schedule.is_occupied(path.steps[0].0, 0));
+        for pair in path.steps.windows(2) {
+            let ((from, t), (to, t2)) = (pair[0], pair[1]);
+            assert_eq!(t + 1, t2);
+            assert!(from.0.abs_diff(to.0) + from.1.abs_diff(to.1) <= 1);
+            assert!(
+                !schedule.blocks_move(from, to, t),
+                "{:?} → {:?} (t={})",
+                from,
+                to,
+                t
+            );
+        }
+    }
+
+    #[test]
+    fn test_wait_for_patrol_to_pass() {
+        // 1本道の廊下を、見回りが縦に横切る（(1,2) と (2,2) を行き来する）
+        //   ###.#
+        //   S...G
+        //   ##.##
+        let grid = Grid::parse("###.#\nS...G\n##.##").unwrap();
+        let schedule = ObstacleSchedule::periodic(vec![vec![(1, 2), (2, 2)]]).unwrap();
+        let bfs = timed_bfs(&grid, (1, 0), (1, 4), &schedule, |&c| c != '#').unwrap();
+        let astar = timed_astar(&grid, (1, 0), (1, 4), &schedule, |&c| c != '#').unwrap();
+        assert_collision_free(&schedule, &bfs);
+        assert_collision_free(&schedule, &astar);
+        assert_eq!(bfs.arrival_time(), astar.arrival_time());
+        // 待たなければ4手だが、(1,2) が空くのを待つ必要がある
+        assert!(bfs.arrival_time() > 4);
+        assert!(bfs.waits() > 0);
+    }
+
+    #[test]
+    fn test_swap_is_a_collision() {
+        // 見回りが (0,1) → (0,0) と動くとき、(0,0) → (0,1) へ動くとすれ違いになる
+        let schedule = ObstacleSchedule::periodic(vec![vec![(0, 1), (0, 0)]]).unwrap();
+        assert!(!schedule.is_occupied((0, 1), 1));
+        assert!(schedule.blocks_move((0, 0), (0, 1), 0));
+
+        // 廊下を行ったり来たりする見回りを、下の脇道でやり過ごす
+        let grid = Grid::parse(
+            "S...G
+.#...",
+        )
+        .unwrap();
+        let patrol = ping_pong(&[(0, 4), (0, 3), (0, 2), (0, 1)]);
+        assert_eq!(patrol, vec![(0, 4), (0, 3), (0, 2), (0, 1), (0, 2), (0, 3)]);
+        let schedule = ObstacleSchedule::periodic(vec![patrol]).unwrap();
+        let bfs = timed_bfs(&grid, (0, 0), (0, 4), &schedule, |&c| c != '#').unwrap();
+        let astar = timed_astar(&grid, (0, 0), (0, 4), &schedule, |&c| c != '#').unwrap();
+        assert_collision_free(&schedule, &bfs);
+        assert_collision_free(&schedule, &astar);
+        assert_eq!(bfs.arrival_time(), astar.arrival_time());
+    }
+
+    #[test]
+    fn test_explicit_schedule() {
+        let grid = Grid::parse("S.G").unwrap();
+        // 時刻1〜3は真ん中が埋まっていて、その後は空く
+        let occupied = vec![vec![], vec![(0, 1)], vec![(0, 1)], vec![(0, 1)], vec![]];
+        let schedule = ObstacleSchedule::Explicit(occupied);
+        let path = timed_astar(&grid, (0, 0), (0, 2), &schedule, |_| true).unwrap();
+        assert_collision_free(&schedule, &path);
+        assert_eq!(path.arrival_time(), 5);
+        assert_eq!(path.waits(), 3);
+        // ずっと埋まっていれば着けない（状態の数が有限なので探索は終わる）
+        let forever = ObstacleSchedule::Explicit(vec![vec![], vec![(0, 1)]]);
+        assert_eq!(timed_bfs(&grid, (0, 0), (0, 2), &forever, |_| true), None);
+        assert!(ObstacleSchedule::periodic(vec![vec![]]).is_err());
+    }
+
+    #[test]
+    fn test_period_is_lcm_and_overflow_is_rejected() {
+        let routes = vec![vec![(0, 0); 4], vec![(0, 1); 6]];
+        match ObstacleSchedule::periodic(routes).unwrap() {
+            ObstacleSchedule::Periodic(patrols) => assert_eq!(patrols.period(), 12),
+            other => panic!("{:?}", other),
+        }
+        // 53以下の素数の長さのルート: 最小公倍数は 2 × 3 × … × 53 で usize を超える
+        let primes = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53];
+        let routes = primes.iter().map(|&len| vec![(0, 0); len]).collect();
+        assert!(ObstacleSchedule::periodic(routes).is_err());
+    }
+}
@@ -0,0 +1,337 @@
+use super::grid::{Grid, DIRECTIONS4, DIRECTIONS8};
+use super::labeling::Connectivity;
+
+// =============================================================================
+// 🔷 グリッドのつながり方（トポロジー）
+// =============================================================================
+//
+// これまでのBFS/DFSは「上下左右の4方向、地図の端で止まる」に決め打ちでした。
+// Topology トレイトは「あるマスから1手で行けるマスはどれか」だけを決めます。
+// BFS/DFS/島の数え上げはこのトレイト越しに隣を聞くので、
+// 同じコードのまま次のような地図を扱えます。
+//
+// - Orthogonal … 上下左右（これまでと同じ）
+// - Diagonal   … 斜めも含む8方向。壁の角をすり抜けてよいかを CornerRule で選ぶ
+// - Hex        … 六角形のマス。6方向
+// - Torus      … 端と端がつながった地図（右端の右は左端、下端の下は上端）
+//
+//   斜め移動の角抜け
+//   S#
+//   #G  ← S から G へ斜めに通れる？
+//
+//   六角形（奇数行が右に半マスずれる）
+//   0行: . . . .
+//   1行:  . . . .
+//   2行: . . . .
+//
+// 隣のマスは呼び出し側が用意した Vec に入れてもらいます。
+// 探索では1つの Vec を使い回すので、マスごとにメモリを確保しません。
+
+pub trait Topology {
+    // pos から1手で行ける隣のマスを out に入れる（out は先に空にする）
+    // rows × cols の地図の範囲内だけ、順番は毎回同じ
+    // open(マス) = そのマスを通れるか（斜め移動の角抜けの判定に使う）
+    fn neighbors_into(
+        &self,
+        rows: usize,
+        cols: usize,
+        pos: (usize, usize),
+        open: &dyn Fn((usize, usize)) -> bool,
+        out: &mut Vec<(usize, usize)>,
+    );
+
+    // 新しい Vec で受け取る版（テストや、1回だけ調べるとき用）
+    fn neighbors(
+        &self,
+        rows: usize,
+        cols: usize,
+        pos: (usize, usize),
+        open: &dyn Fn((usize, usize)) -> bool,
+    ) -> Vec<(usize, usize)> {
+        let mut out = Vec::new();
+        self.neighbors_into(rows, cols, pos, open, &mut out);
+        out
+    }
+}
+
+// 隣のマスのうち、通れるマスだけを out に入れる
+pub fn open_neighbors<T, Topo, F>(
+    grid: &Grid<T>,
+    topology: &Topo,
+    pos: (usize, usize),
+    passable: F,
+    out: &mut Vec<(usize, usize)>,
+) where
+    Topo: Topology + ?Sized,
+    F: Fn(&T) -> bool,
+{
+    let open = |cell: (usize, usize)| passable(&grid[cell]);
+    topology.neighbors_into(grid.rows(), grid.cols(), pos, &open, out);
+    out.retain(|&cell| open(cell));
+}
+
+// 範囲内なら (row + dr, col + dc)
+fn offset(
+    rows: usize,
+    cols: usize,
+    (row, col): (usize, usize),
+    (dr, dc): (isize, isize),
+) -> Option<(usize, usize)> {
+    let r = row.checked_add_signed(dr)?;
+    let c = col.checked_add_signed(dc)?;
+    (r < rows && c < cols).then_some((r, c))
+}
+
+// 上下左右
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Orthogonal;
+
+impl Topology for Orthogonal {
+    fn neighbors_into(
+        &self,
+        rows: usize,
+        cols: usize,
+        pos: (usize, usize),
+        _open: &dyn Fn((usize, usize)) -> bool,
+        out: &mut Vec<(usize, usize)>,
+    ) {
+        out.clear();
+        out.extend(
+            DIRECTIONS4
+                .iter()
+                .filter_map(|&d| offset(rows, cols, pos, d)),
+        );
+    }
+}
+
+// 斜め移動で壁の角をすり抜けてよいか
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CornerRule {
+    // いつでも斜めに動ける
+    Allow,
+    // 両側の2マスが両方とも壁のときだけ通れない（隙間に体をねじ込まない）
+    NoSqueezing,
+    // 両側の2マスが両方とも通れるときだけ斜めに動ける（A*と同じ）
+    NoCutting,
+}
+
+// 斜めも含む8方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Diagonal(pub CornerRule);
+
+impl Topology for Diagonal {
+    fn neighbors_into(
+        &self,
+        rows: usize,
+        cols: usize,
+        pos: (usize, usize),
+        open: &dyn Fn((usize, usize)) -> bool,
+        out: &mut Vec<(usize, usize)>,
+    ) {
+        out.clear();
+        let candidates = DIRECTIONS8
+            .iter()
+            .filter(|&&(dr, dc)| {
+                if dr == 0 || dc == 0 || self.0 == CornerRule::Allow {
+                    return true;
+                }
+                // 斜め移動の両側のマス（縦に1つ、横に1つ）
+                let side_open = |d| offset(rows, cols, pos, d).is_some_and(open);
+                let (vertical, horizontal) = (side_open((dr, 0)), side_open((0, dc)));
+                match self.0 {
+                    CornerRule::NoSqueezing => vertical || horizontal,
+                    _ => vertical && horizontal,
+                }
+            })
+            .filter_map(|&d| offset(rows, cols, pos, d));
+        out.extend(candidates);
+    }
+}
+
+// 六角形のマスを長方形のグリッドにどう並べるか
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hex {
+    // アキシャル座標: grid[(r, q)]。6方向は (0,±1), (±1,0), (-1,+1), (+1,-1)
+    // 地図は平行四辺形をななめに押しつぶした形になる
+    Axial,
+    // オフセット座標（odd-r）: 奇数行が右に半マスずれて並ぶ。見た目どおりの長方形
+    OddRow,
+}
+
+const AXIAL_DIRECTIONS: [(isize, isize); 6] = [(1, 0), (0, 1), (-1, 0), (0, -1), (-1, 1), (1, -1)];
+const EVEN_ROW_DIRECTIONS: [(isize, isize); 6] =
+    [(1, 0), (0, 1), (-1, 0), (0, -1), (-1, -1), (1, -1)];
+const ODD_ROW_DIRECTIONS: [(isize, isize); 6] = [(1, 0), (0, 1), (-1, 0), (0, -1), (-1, 1), (1, 1)];
+
+impl Topology for Hex {
+    fn neighbors_into(
+        &self,
+        rows: usize,
+        cols: usize,
+        pos: (usize, usize),
+        _open: &dyn Fn((usize, usize)) -> bool,
+        out: &mut Vec<(usize, usize)>,
+    ) {
+        let directions = match self {
+            Hex::Axial => &AXIAL_DIRECTIONS,
+            Hex::OddRow if pos.0.is_multiple_of(2) => &EVEN_ROW_DIRECTIONS,
+            Hex::OddRow => &ODD_ROW_DIRECTIONS,
+        };
+        out.clear();
+        out.extend(
+            directions
+                .iter()
+                .filter_map(|&d| offset(rows, cols, pos, d)),
+        );
+    }
+}
+
+// 端と端がつながった地図（ドーナツの表面）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Torus {
+    // 斜めにも動けるか
+    pub diagonal: bool,
+}
+
+impl Topology for Torus {
+    fn neighbors_into(
+        &self,
+        rows: usize,
+        cols: usize,
+        (row, col): (usize, usize),
+        _open: &dyn Fn((usize, usize)) -> bool,
+        out: &mut Vec<(usize, usize)>,
+    ) {
+        let directions: &[(isize, isize)] = if self.diagonal {
+            &DIRECTIONS8
+        } else {
+            &DIRECTIONS4
+        };
+        out.clear();
+        for &(dr, dc) in directions {
+            // rows を足してから割った余りにすると、-1 が rows - 1 になる
+            let r = (row + rows).wrapping_add_signed(dr) % rows;
+            let c = (col + cols).wrapping_add_signed(dc) % cols;
+            // 細い地図では同じマスに何方向からも着くので、重複と自分自身は除く
+            if (r, c) != (row, col) && !out.contains(&(r, c)) {
+                out.push((r, c));
+            }
+        }
+    }
+}
+
+// これまでの Connectivity もトポロジーとして使える
+impl Topology for Connectivity {
+    fn neighbors_into(
+        &self,
+        rows: usize,
+        cols: usize,
+        pos: (usize, usize),
+        open: &dyn Fn((usize, usize)) -> bool,
+        out: &mut Vec<(usize, usize)>,
+    ) {
+        match self {
+            Connectivity::Four => Orthogonal.neighbors_into(rows, cols, pos, open, out),
+            Connectivity::Eight => {
+                Diagonal(CornerRule::Allow).neighbors_into(rows, cols, pos, open, out)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dfs_bfs_practice::dfs::count_islands_on;
+    use crate::dfs_bfs_practice::maze::bfs_shortest_path_on;
+    use crate::trace::NoTrace;
+
+    fn all_topologies() -> Vec<(&'static str, Box<dyn Topology>)> {
+        vec![
+            ("4近傍", Box::new(Orthogonal)),
+            ("8近傍", Box::new(Diagonal(CornerRule::Allow))),
+            ("六角形（アキシャル）", Box::new(Hex::Axial)),
+            ("六角形（奇数行ずらし）", Box::new(Hex::OddRow)),
+            ("トーラス", Box::new(Torus { diagonal: false })),
+            ("トーラス（8方向）", Box::new(Torus { diagonal: true })),
+        ]
+    }
+
+    #[test]
+    fn test_neighbors_are_symmetric() {
+        // 全部通れる地図では「aの隣にbがいる ⇔ bの隣にaがいる」
+        let open = |_: (usize, usize)| true;
+        for (name, topology) in all_topologies() {
+            for &(rows, cols) in &[(1, 1), (2, 3), (5, 6)] {
+                let grid = Grid::new(rows, cols, ());
+                for a in grid.positions() {
+                    for b in topology.neighbors(rows, cols, a, &open) {
+                        assert!(
+                            topology.neighbors(rows, cols, b, &open).contains(&a),
+                            "{} {:?} {:?}",
+                            name,
+                            a,
+                            b
+                        );
+                    }
+                }
+            }
+        }
+        let count = |topology: &dyn Topology| topology.neighbors(5, 6, (2, 2), &open).len();
+        assert_eq!(count(&Hex::Axial), 6);
+        assert_eq!(count(&Hex::OddRow), 6);
+        assert_eq!(count(&Torus { diagonal: true }), 8);
+        assert_eq!(
+            Torus { diagonal: false }.neighbors(5, 6, (0, 0), &open),
+            vec![(1, 0), (0, 1), (4, 0), (0, 5)]
+        );
+    }
+
+    #[test]
+    fn test_corner_rules() {
+        let grid = Grid::parse("S#\n#G").unwrap();
+        let passable = |&c: &char| c != '#';
+        let reaches = |grid: &Grid<char>, rule| {
+            let mut out = Vec::new();
+            open_neighbors(grid, &Diagonal(rule), (0, 0), passable, &mut out);
+            out.contains(&(1, 1))
+        };
+        assert!(reaches(&grid, CornerRule::Allow));
+        assert!(!reaches(&grid, CornerRule::NoSqueezing));
+        assert!(!reaches(&grid, CornerRule::NoCutting));
+
+        let grid = Grid::parse("S.\n#G").unwrap();
+        assert!(reaches(&grid, CornerRule::NoSqueezing));
+        assert!(!reaches(&grid, CornerRule::NoCutting));
+    }
+
+    #[test]
+    fn test_search_and_islands_on_each_topology() {
+        let grid = Grid::parse("#..\n.#.\n..#").unwrap();
+        let is_land = |&c: &char| c == '#';
+        let counts: Vec<usize> = all_topologies()
+            .iter()
+            .map(|(_, topology)| count_islands_on(&grid, topology.as_ref(), is_land).0)
+            .collect();
+        assert_eq!(counts, vec![3, 1, 3, 2, 3, 1]);
+
+        // トーラスでは端を越えた方が近い
+        let corridor = Grid::parse("S...G").unwrap();
+        let passable = |&c: &char| c != '#';
+        let steps = |topology: &dyn Topology| {
+            bfs_shortest_path_on(&corridor, topology, (0, 0), (0, 4), passable, &mut NoTrace)
+                .path
+                .map(|p| p.steps())
+        };
+        assert_eq!(steps(&Orthogonal), Some(4));
+        assert_eq!(steps(&Torus { diagonal: false }), Some(1));
+        let walled = Grid::parse("S#\n#G").unwrap();
+        let walled_steps = |topology: &dyn Topology| {
+            bfs_shortest_path_on(&walled, topology, (0, 0), (1, 1), passable, &mut NoTrace)
+                .path
+                .map(|p| p.steps())
+        };
+        assert_eq!(walled_steps(&Diagonal(CornerRule::Allow)), Some(1));
+        assert_eq!(walled_steps(&Diagonal(CornerRule::NoCutting)), None);
+    }
+}
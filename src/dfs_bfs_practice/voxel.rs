@@ -0,0 +1,321 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::ops::{Index, IndexMut};
+
+use super::grid::{Grid, DIRECTIONS4};
+
+// =============================================================================
+// 🏢 3次元のグリッド（複数階の倉庫）
+// =============================================================================
+//
+// Grid<T> を階の数だけ重ねたものです。マスは (階, 行, 列) で表します。
+// テキストでは、階と階の間を空行で区切ります（上から1階、2階、...）。
+//
+//   S.#          1階
+//   .^#
+//
+//   ..E          2階
+//   .v.
+//
+// 移動のルール（BFSの1手）:
+// - 同じ階の上下左右（'#' は壁）
+// - '^' = 上り階段: 1つ上の階の同じ位置へ
+// - 'v' = 下り階段: 1つ下の階の同じ位置へ
+// - 'E' = エレベーター: 同じ位置にある別の階の 'E' へ（何階でも1手）
+//
+// 階段やエレベーターを無視して、上下左右 + 上下の階の6方向でつながった
+// かたまり（6近傍の連結成分）を数えることもできます。
+
+// 6方向（同じ階の4方向 + 上の階 + 下の階）
+const DIRECTIONS6: [(isize, isize, isize); 6] = [
+    (0, 1, 0),
+    (0, 0, 1),
+    (0, -1, 0),
+    (0, 0, -1),
+    (1, 0, 0),
+    (-1, 0, 0),
+];
+
+pub type Voxel = (usize, usize, usize);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid3<T> {
+    // floors[階] = その階の地図（全ての階で大きさが同じ）
+    floors: Vec<Grid<T>>,
+}
+
+impl<T> Grid3<T> {
+    // 全てのマスを同じ値で埋めた3次元グリッドを作る
+    pub fn new(floors: usize, rows: usize, cols: usize, fill: T) -> Grid3<T>
+    where
+        T: Clone,
+    {
+        Grid3 {
+            floors: vec![Grid::new(rows, cols, fill); floors],
+        }
+    }
+
+    // 階ごとの地図から作る（大きさが揃っていなければエラー）
+    pub fn from_floors(floors: Vec<Grid<T>>) -> Result<Grid3<T>, String> {
+        if let Some(first) = floors.first() {
+            for (i, floor) in floors.iter().enumerate() {
+                if (floor.rows(), floor.cols()) != (first.rows(), first.cols()) {
+                    return Err(format!(
+                        "{}階の大きさが{}×{}です（1階は{}×{}）",
+                        i + 1,
+                        floor.rows(),
+                        floor.cols(),
+                        first.rows(),
+                        first.cols()
+                    ));
+                }
+            }
+        }
+        Ok(Grid3 { floors })
+    }
+
+    pub fn floors(&self) -> usize {
+        self.floors.len()
+    }
+
+    pub fn rows(&self) -> usize {
+        self.floors.first().map_or(0, |floor| floor.rows())
+    }
+
+    pub fn cols(&self) -> usize {
+        self.floors.first().map_or(0, |floor| floor.cols())
+    }
+
+    pub fn floor(&self, floor: usize) -> &Grid<T> {
+        &self.floors[floor]
+    }
+
+    // 全てのマスの座標（1階の左上から順に）
+    pub fn positions(&self) -> impl Iterator<Item = Voxel> {
+        let (floors, rows, cols) = (self.floors(), self.rows(), self.cols());
+        (0..floors)
+            .flat_map(move |f| (0..rows).flat_map(move |r| (0..cols).map(move |c| (f, r, c))))
+    }
+
+    // 条件を満たす最初のマス
+    pub fn find<F: Fn(&T) -> bool>(&self, pred: F) -> Option<Voxel> {
+        self.positions().find(|&pos| pred(&self[pos]))
+    }
+
+    // 6方向の隣のマス（範囲外は含まない）
+    pub fn neighbors6(&self, (f, r, c): Voxel) -> impl Iterator<Item = Voxel> {
+        let (floors, rows, cols) = (self.floors(), self.rows(), self.cols());
+        DIRECTIONS6.iter().filter_map(move |&(df, dr, dc)| {
+            let next = (
+                f.checked_add_signed(df)?,
+                r.checked_add_signed(dr)?,
+                c.checked_add_signed(dc)?,
+            );
+            (next.0 < floors && next.1 < rows && next.2 < cols).then_some(next)
+        })
+    }
+}
+
+impl<T> Index<Voxel> for Grid3<T> {
+    type Output = T;
+
+    fn index(&self, (floor, row, col): Voxel) -> &T {
+        &self.floors[floor][(row, col)]
+    }
+}
+
+impl<T> IndexMut<Voxel> for Grid3<T> {
+    fn index_mut(&mut self, (floor, row, col): Voxel) -> &mut T {
+        &mut self.floors[floor][(row, col)]
+    }
+}
+
+impl Grid3<char> {
+    // 空行で区切られた階をまとめて読み込む
+    pub fn parse(text: &str) -> Result<Grid3<char>, String> {
+        let mut floors = Vec::new();
+        let mut current = Vec::new();
+        for line in text
+            .lines()
+            .map(|line| line.trim_end_matches('\r'))
+            .chain([""])
+        {
+            if line.trim().is_empty() {
+                if !current.is_empty() {
+                    let floor = Grid::parse(&current.join("\n"))
+                        .map_err(|e| format!("{}階: {}", floors.len() + 1, e))?;
+                    floors.push(floor);
+                    current.clear();
+                }
+            } else {
+                current.push(line);
+            }
+        }
+        if floors.is_empty() {
+            return Err("階が1つもありません".to_string());
+        }
+        Grid3::from_floors(floors)
+    }
+
+    // ファイルから読み込む
+    pub fn load(path: &str) -> Result<Grid3<char>, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("ファイルを開けません({}): {}", path, e))?;
+        Grid3::parse(&text)
+    }
+
+    // BFSの1手で行けるマス（同じ階の上下左右、階段、エレベーター）
+    pub fn moves(&self, (f, r, c): Voxel) -> Vec<Voxel> {
+        let floor = &self.floors[f];
+        let mut result: Vec<Voxel> = floor
+            .neighbors_in((r, c), &DIRECTIONS4)
+            .map(|(nr, nc)| (f, nr, nc))
+            .collect();
+        match self[(f, r, c)] {
+            '^' if f + 1 < self.floors() => result.push((f + 1, r, c)),
+            'v' if f > 0 => result.push((f - 1, r, c)),
+            'E' => result.extend(
+                (0..self.floors())
+                    .filter(|&other| other != f && self[(other, r, c)] == 'E')
+                    .map(|other| (other, r, c)),
+            ),
+            _ => {}
+        }
+        result.retain(|&next| self[next] != '#');
+        result
+    }
+}
+
+// 'S' から 'G' までの最短経路（通るマスの列）をBFSで求める
+// S か G がなければエラー、到達できなければNone
+pub fn voxel_shortest_path(grid: &Grid3<char>) -> Result<Option<Vec<Voxel>>, String> {
+    let start = grid
+        .find(|&c| c == 'S')
+        .ok_or("地図にスタート 'S' がありません")?;
+    let goal = grid
+        .find(|&c| c == 'G')
+        .ok_or("地図にゴール 'G' がありません")?;
+
+    // parent[マス] = そのマスに来る直前のマス（訪問済みの記録も兼ねる、スタートは自分自身）
+    let mut parent = Grid3::new(grid.floors(), grid.rows(), grid.cols(), None);
+    let mut queue = VecDeque::new();
+    parent[start] = Some(start);
+    queue.push_back(start);
+
+    while let Some(current) = queue.pop_front() {
+        if current == goal {
+            let mut path = vec![goal];
+            let mut cell = goal;
+            while cell != start {
+                cell = parent[cell].unwrap_or(start);
+                path.push(cell);
+            }
+            path.reverse();
+            return Ok(Some(path));
+        }
+        for next in grid.moves(current) {
+            if parent[next].is_none() {
+                parent[next] = Some(current);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+// 6近傍でつながったかたまりの数と、一番大きいかたまりのマスの数
+pub fn count_components_6<T, F>(grid: &Grid3<T>, is_solid: F) -> (usize, usize)
+where
+    F: Fn(&T) -> bool,
+{
+    let mut seen = Grid3::new(grid.floors(), grid.rows(), grid.cols(), false);
+    let (mut count, mut largest) = (0, 0);
+    let mut queue = VecDeque::new();
+
+    for start in grid.positions() {
+        if seen[start] || !is_solid(&grid[start]) {
+            continue;
+        }
+        seen[start] = true;
+        queue.push_back(start);
+        let mut size = 0;
+        while let Some(current) = queue.pop_front() {
+            size += 1;
+            for next in grid.neighbors6(current) {
+                if !seen[next] && is_solid(&grid[next]) {
+                    seen[next] = true;
+                    queue.push_back(next);
+                }
+            }
+        }
+        count += 1;
+        largest = largest.max(size);
+    }
+
+    (count, largest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn warehouse() -> Grid3<char> {
+        Grid3::parse(
+            "\
+S.#.E
+.##.#
+...^#
+
+####E
+#...#
+#.#v#
+
+..G.E
+.####
+.....",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_layers() {
+        let grid = warehouse();
+        assert_eq!((grid.floors(), grid.rows(), grid.cols()), (3, 3, 5));
+        assert_eq!(grid[(1, 2, 3)], 'v');
+        assert_eq!(grid.find(|&c| c == 'G'), Some((2, 0, 2)));
+        assert!(Grid3::parse("S.\n..\n\n...\n...").is_err());
+        assert!(Grid3::parse("\n\n").is_err());
+    }
+
+    #[test]
+    fn test_stairs_and_elevator() {
+        let grid = warehouse();
+        let path = voxel_shortest_path(&grid).unwrap().unwrap();
+        // 1階の '^' から2階へは上れるが、2階からは3階へ行けない
+        // 1階右上のエレベーター（8手）で3階へ直行し、2手でゴール
+        assert_eq!(path.first(), Some(&(0, 0, 0)));
+        assert_eq!(path.last(), Some(&(2, 0, 2)));
+        assert!(path.contains(&(0, 0, 4)) && path.contains(&(2, 0, 4)));
+        assert_eq!(path.len() - 1, 8 + 1 + 2);
+        for pair in path.windows(2) {
+            assert!(grid.moves(pair[0]).contains(&pair[1]));
+        }
+        // 下り階段では上れない
+        assert!(!grid.moves((1, 2, 3)).contains(&(2, 2, 3)));
+        assert!(grid.moves((1, 2, 3)).contains(&(0, 2, 3)));
+
+        let sealed = Grid3::parse("S#\n##\n\n#G\n##").unwrap();
+        assert_eq!(voxel_shortest_path(&sealed).unwrap(), None);
+        assert!(voxel_shortest_path(&Grid3::parse("S.").unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_six_connected_components() {
+        // 1階と2階で同じ位置に重なっている '#' はつながる（斜めはつながらない）
+        let grid = Grid3::parse("#..\n...\n..#\n\n#..\n.#.\n...").unwrap();
+        assert_eq!(count_components_6(&grid, |&c| c == '#'), (3, 2));
+        let solid = Grid3::from_floors(vec![Grid::new(4, 4, true); 5]).unwrap();
+        assert_eq!(count_components_6(&solid, |&b| b), (1, 80));
+    }
+}
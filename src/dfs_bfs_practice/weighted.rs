@@ -0,0 +1,228 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+
+use super::grid::{Grid, Legend};
+use super::path::{trace_path, GridPath};
+use crate::trace::SearchObserver;
+
+// =============================================================================
+// ⛰️ 重み付きグリッドの最短経路: ダイクストラ法と0-1 BFS
+// =============================================================================
+//
+// maze_shortest_path_bfs.rs のBFSは「通れる / 通れない」の2種類しか扱えません。
+// ここではマスごとに「入るのにかかるコスト」があるグリッドを考えます。
+//
+//   '.' = 道（1）, ':' = 砂地（3）, '~' = 沼（5）, '#' = 壁（通れない）
+//
+// コストがバラバラなら、BFSの代わりにダイクストラ法を使います
+// （dijkstra モジュールと同じく、BinaryHeap + Reverse で安い順に取り出す）。
+//
+// コストが0か1だけのとき（例: 通路は0、壁を壊して進むと1）は、
+// ヒープの代わりに両端キュー（VecDeque）で済みます。
+//   コスト0の移動 → 先頭に入れる（今と同じ距離なので先に調べる）
+//   コスト1の移動 → 末尾に入れる（BFSと同じ）
+// これを0-1 BFSと呼び、O(マスの数) で最短経路が求まります。
+
+// 地形
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Terrain {
+    Road,
+    Sand,
+    Swamp,
+    Wall,
+}
+
+impl Terrain {
+    // そのマスに入るコスト（壁はNone）
+    pub fn cost(self) -> Option<u32> {
+        match self {
+            Terrain::Road => Some(1),
+            Terrain::Sand => Some(3),
+            Terrain::Swamp => Some(5),
+            Terrain::Wall => None,
+        }
+    }
+
+    // 地形の凡例（'S' と 'G' は道として読む）
+    pub fn legend() -> Legend<Terrain> {
+        Legend::new()
+            .with('.', Terrain::Road)
+            .with('S', Terrain::Road)
+            .with('G', Terrain::Road)
+            .with(':', Terrain::Sand)
+            .with('~', Terrain::Swamp)
+            .with('#', Terrain::Wall)
+    }
+}
+
+// グリッド上のダイクストラ法
+// cost(マスの値) = そのマスに入るコスト（Noneなら通れない）
+// スタートのマスのコストは数えない。到達できなければNone
+pub fn grid_dijkstra<T, F, O>(
+    grid: &Grid<T>,
+    start: (usize, usize),
+    goal: (usize, usize),
+    cost: F,
+    observer: &mut O,
+) -> Option<GridPath>
+where
+    F: Fn(&T) -> Option<u32>,
+    O: SearchObserver<(usize, usize)>,
+{
+    let mut distance: Grid<u64> = Grid::new(grid.rows(), grid.cols(), u64::MAX);
+    let mut parent: Grid<Option<(usize, usize)>> = Grid::new(grid.rows(), grid.cols(), None);
+    let mut heap = BinaryHeap::new();
+
+    distance[start] = 0;
+    heap.push(Reverse((0u64, start)));
+
+    while let Some(Reverse((current_cost, current))) = heap.pop() {
+        observer.on_pop(current, current_cost);
+        // もっと安いコストで既に確定している（古い情報）
+        if current_cost > distance[current] {
+            observer.on_skip_stale(current, current_cost);
+            continue;
+        }
+        observer.on_visit(current, current_cost);
+        if current == goal {
+            return Some(GridPath {
+                cost: current_cost,
+                cells: trace_path(&parent, goal),
+            });
+        }
+
+        for next in grid.neighbors4(current) {
+            let step = match cost(&grid[next]) {
+                Some(step) => step as u64,
+                None => continue,
+            };
+            let new_cost = current_cost + step;
+            if new_cost < distance[next] {
+                distance[next] = new_cost;
+                parent[next] = Some(current);
+                observer.on_relax(current, next, new_cost);
+                heap.push(Reverse((new_cost, next)));
+            }
+        }
+    }
+
+    None
+}
+
+// 0-1 BFS: マスに入るコストが0か1だけのときの最短経路
+// cost が0と1以外を返したらエラー
+pub fn zero_one_bfs<T, F, O>(
+    grid: &Grid<T>,
+    start: (usize, usize),
+    goal: (usize, usize),
+    cost: F,
+    observer: &mut O,
+) -> Result<Option<GridPath>, String>
+where
+    F: Fn(&T) -> Option<u32>,
+    O: SearchObserver<(usize, usize)>,
+{
+    let mut distance: Grid<u64> = Grid::new(grid.rows(), grid.cols(), u64::MAX);
+    let mut parent: Grid<Option<(usize, usize)>> = Grid::new(grid.rows(), grid.cols(), None);
+    let mut done = Grid::new(grid.rows(), grid.cols(), false);
+    let mut deque = VecDeque::new();
+
+    distance[start] = 0;
+    deque.push_back(start);
+
+    while let Some(current) = deque.pop_front() {
+        let current_cost = distance[current];
+        observer.on_pop(current, current_cost);
+        // 同じマスが2回入ることがある（後から0のコストで更新された場合）
+        if done[current] {
+            observer.on_skip_stale(current, current_cost);
+            continue;
+        }
+        done[current] = true;
+        observer.on_visit(current, current_cost);
+        if current == goal {
+            return Ok(Some(GridPath {
+                cost: current_cost,
+                cells: trace_path(&parent, goal),
+            }));
+        }
+
+        for next in grid.neighbors4(current) {
+            let step = match cost(&grid[next]) {
+                Some(step) if step <= 1 => step as u64,
+                Some(step) => {
+                    return Err(format!(
+                        "({}, {}) のコストが {} です（0-1 BFSは0か1だけ扱えます）",
+                        next.0, next.1, step
+                    ))
+                }
+                None => continue,
+            };
+            let new_cost = current_cost + step;
+            if new_cost < distance[next] {
+                distance[next] = new_cost;
+                parent[next] = Some(current);
+                observer.on_relax(current, next, new_cost);
+                // コスト0なら先頭、1なら末尾
+                if step == 0 {
+                    deque.push_front(next);
+                } else {
+                    deque.push_back(next);
+                }
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trace::NoTrace;
+
+    #[test]
+    fn test_dijkstra_goes_around_swamp() {
+        // まっすぐ沼を抜けるとコスト 5+5+1=11、上の道を回ると 1×5=5
+        let text = "\
+......
+S~~G..
+######";
+        let grid = Grid::parse_with(text, &Terrain::legend()).unwrap();
+        let path = grid_dijkstra(&grid, (1, 0), (1, 3), |t| t.cost(), &mut NoTrace).unwrap();
+        assert_eq!(path.cost, 5);
+        assert_eq!(path.cells.first(), Some(&(1, 0)));
+        assert_eq!(path.cells.last(), Some(&(1, 3)));
+        let total: u64 = path.cells[1..]
+            .iter()
+            .map(|&c| grid[c].cost().unwrap() as u64)
+            .sum();
+        assert_eq!(total, path.cost);
+        assert_eq!(
+            grid_dijkstra(&grid, (1, 0), (2, 0), |t| t.cost(), &mut NoTrace),
+            None
+        );
+    }
+
+    #[test]
+    fn test_zero_one_bfs_counts_broken_walls() {
+        // 壁を壊すとコスト1、通路は0: 壊す壁の最小数
+        let grid = Grid::parse(
+            "\
+S.#..
+###.#
+..#.G",
+        )
+        .unwrap();
+        let wall_cost = |&c: &char| Some(if c == '#' { 1 } else { 0 });
+        let path = zero_one_bfs(&grid, (0, 0), (2, 4), wall_cost, &mut NoTrace)
+            .unwrap()
+            .unwrap();
+        assert_eq!(path.cost, 1);
+        assert_eq!(path.cells.iter().filter(|&&c| grid[c] == '#').count(), 1);
+        // 同じ問題をダイクストラ法で解いても同じコスト
+        let dijkstra = grid_dijkstra(&grid, (0, 0), (2, 4), wall_cost, &mut NoTrace).unwrap();
+        assert_eq!(dijkstra.cost, path.cost);
+        assert!(zero_one_bfs(&grid, (0, 0), (2, 4), |_| Some(2), &mut NoTrace).is_err());
+    }
+}
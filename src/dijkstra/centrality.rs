@@ -0,0 +1,233 @@
+use super::{count_shortest_paths, dijkstra, Graph};
+
+// =============================================================================
+// 🏢 中心性指標: どの配送センターが重要なハブか？
+// =============================================================================
+//
+// - 媒介中心性（Betweenness）: 他の2点間の最短経路上に何回現れるか
+//   → 値が大きいセンターが止まると、多くの配送ルートが影響を受ける
+// - 近接中心性（Closeness）: 他の全センターへの距離が近いほど大きい
+// - 離心数（Eccentricity）: 一番遠いセンターまでの距離
+//   → 直径 = 離心数の最大値、半径 = 離心数の最小値
+//
+// 媒介中心性は Brandes のアルゴリズムで求めます。
+// 練習問題3と同じ count_shortest_paths でダイクストラ法の途中に
+// 「最短経路の本数」を数え、その後でノードが確定した順の逆に依存度を集計します。
+// 確定した順は最短経路のDAGの順番になっているので、コスト0の辺があっても正しく集計できます。
+
+// 媒介中心性（Brandesのアルゴリズム）
+// 順序付きペア(s, t)ごとに数えるので、無向グラフ（両方向に辺がある）では
+// undirected = true にすると同じ経路の二重カウントを除く
+pub fn betweenness_centrality(graph: &Graph, undirected: bool) -> Vec<f64> {
+    let n = graph.len();
+    let mut centrality = vec![0.0; n];
+
+    for start in 0..n {
+        let paths = count_shortest_paths(graph, start);
+        // dependency[v] = 始点startから見たvの依存度
+        let mut dependency = vec![0.0; n];
+
+        // 確定した順の逆（遠いノードから）に、直前のノードへ依存度を配る
+        for &w in paths.order.iter().rev() {
+            for &v in &paths.predecessors[w] {
+                let share = paths.path_count[v] as f64 / paths.path_count[w] as f64;
+                dependency[v] += share * (1.0 + dependency[w]);
+            }
+            if w != start {
+                centrality[w] += dependency[w];
+            }
+        }
+    }
+
+    if undirected {
+        for value in centrality.iter_mut() {
+            *value /= 2.0;
+        }
+    }
+    centrality
+}
+
+// 近接中心性
+// 到達できるノード数をr、そこまでの距離の合計をsumとして (r - 1) / sum
+// どこにも行けないノードは0
+pub fn closeness_centrality(graph: &Graph) -> Vec<f64> {
+    (0..graph.len())
+        .map(|node| {
+            let reachable: Vec<u32> = dijkstra(graph, node)
+                .into_iter()
+                .filter(|&d| d != u32::MAX)
+                .collect();
+            let total: u64 = reachable.iter().map(|&d| d as u64).sum();
+            if total == 0 {
+                0.0
+            } else {
+                (reachable.len() - 1) as f64 / total as f64
+            }
+        })
+        .collect()
+}
+
+// 離心数: 各ノードから一番遠いノードまでの距離
+// 到達できないノードがある場合は無限大としてNone
+pub fn eccentricity(graph: &Graph) -> Vec<Option<u32>> {
+    (0..graph.len())
+        .map(|node| {
+            let distances = dijkstra(graph, node);
+            if distances.contains(&u32::MAX) {
+                None
+            } else {
+                distances.into_iter().max()
+            }
+        })
+        .collect()
+}
+
+// 直径: 離心数の最大値（1つでも無限大ならNone）
+pub fn diameter(eccentricities: &[Option<u32>]) -> Option<u32> {
+    eccentricities
+        .iter()
+        .copied()
+        .collect::<Option<Vec<u32>>>()?
+        .into_iter()
+        .max()
+}
+
+// 半径: 離心数の最小値（有限のものだけで考える）
+pub fn radius(eccentricities: &[Option<u32>]) -> Option<u32> {
+    eccentricities.iter().flatten().copied().min()
+}
+
+// 1ノード分の指標
+#[derive(Debug, Clone, PartialEq)]
+pub struct CentralityRow {
+    pub node: usize,
+    pub betweenness: f64,
+    pub closeness: f64,
+    pub eccentricity: Option<u32>,
+}
+
+// 全ノードの指標をまとめたレポート（媒介中心性の大きい順に並ぶ）
+#[derive(Debug, Clone, PartialEq)]
+pub struct CentralityReport {
+    pub rows: Vec<CentralityRow>,
+    pub diameter: Option<u32>,
+    pub radius: Option<u32>,
+}
+
+impl CentralityReport {
+    pub fn new(graph: &Graph, undirected: bool) -> CentralityReport {
+        let betweenness = betweenness_centrality(graph, undirected);
+        let closeness = closeness_centrality(graph);
+        let eccentricities = eccentricity(graph);
+
+        let mut rows: Vec<CentralityRow> = (0..graph.len())
+            .map(|node| CentralityRow {
+                node,
+                betweenness: betweenness[node],
+                closeness: closeness[node],
+                eccentricity: eccentricities[node],
+            })
+            .collect();
+        // 媒介中心性 → 近接中心性 の順で大きいものを上に（同点はノード番号順）
+        rows.sort_by(|a, b| {
+            b.betweenness
+                .total_cmp(&a.betweenness)
+                .then(b.closeness.total_cmp(&a.closeness))
+                .then(a.node.cmp(&b.node))
+        });
+
+        CentralityReport {
+            rows,
+            diameter: diameter(&eccentricities),
+            radius: radius(&eccentricities),
+        }
+    }
+
+    // ランキング表を文字列にする
+    // label: ノード番号を表示名に変換する関数（例: 0 → "A"）
+    pub fn to_table<F: Fn(usize) -> String>(&self, label: F) -> String {
+        let mut table = String::new();
+        table.push_str("順位  ノード  媒介中心性  近接中心性  離心数\n");
+        for (rank, row) in self.rows.iter().enumerate() {
+            let ecc = match row.eccentricity {
+                Some(e) => e.to_string(),
+                None => "∞".to_string(),
+            };
+            table.push_str(&format!(
+                "{:>4}  {:>6}  {:>10.3}  {:>10.3}  {:>6}\n",
+                rank + 1,
+                label(row.node),
+                row.betweenness,
+                row.closeness,
+                ecc
+            ));
+        }
+        let show = |value: Option<u32>| value.map_or("∞".to_string(), |v| v.to_string());
+        table.push_str(&format!(
+            "直径: {}, 半径: {}\n",
+            show(self.diameter),
+            show(self.radius)
+        ));
+        table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_betweenness_on_path_graph() {
+        // 0 - 1 - 2 の一直線: 真ん中だけが経由地になる
+        let graph: Graph = vec![vec![(1, 1)], vec![(0, 1), (2, 1)], vec![(1, 1)]];
+        assert_eq!(betweenness_centrality(&graph, true), vec![0.0, 1.0, 0.0]);
+        assert_eq!(betweenness_centrality(&graph, false), vec![0.0, 2.0, 0.0]);
+    }
+
+    #[test]
+    fn test_betweenness_splits_equal_paths() {
+        // A→B→D と A→C→D が同じコスト: BとCで半分ずつ
+        let graph: Graph = vec![vec![(1, 10), (2, 10)], vec![(3, 10)], vec![(3, 10)], vec![]];
+        assert_eq!(
+            betweenness_centrality(&graph, false),
+            vec![0.0, 0.5, 0.5, 0.0]
+        );
+    }
+
+    #[test]
+    fn test_betweenness_with_zero_cost_edges() {
+        // 0 → 2 はコスト1の直行と、0 → 1 →(0) 2 の同じコストの2本: 1は半分だけ経由地
+        let graph: Graph = vec![vec![(1, 0), (2, 1)], vec![(2, 1)], vec![]];
+        assert_eq!(betweenness_centrality(&graph, false), vec![0.0, 0.5, 0.0]);
+
+        // 0 と 1 がコスト0で行き来できても、0 → 2 は必ず1を通る
+        let graph: Graph = vec![vec![(1, 0)], vec![(0, 0), (2, 1)], vec![]];
+        assert_eq!(betweenness_centrality(&graph, false), vec![0.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_report_delivery_network() {
+        let graph: Graph = vec![
+            vec![(1, 5), (2, 2)],
+            vec![(0, 5), (2, 3), (3, 7)],
+            vec![(0, 2), (1, 3), (3, 1), (4, 4)],
+            vec![(1, 7), (2, 1), (4, 6)],
+            vec![(2, 4), (3, 6)],
+        ];
+        let report = CentralityReport::new(&graph, true);
+        // Cが一番のハブ
+        assert_eq!(report.rows[0].node, 2);
+        assert_eq!(report.diameter, Some(7));
+        assert_eq!(report.radius, Some(4));
+    }
+
+    #[test]
+    fn test_disconnected_eccentricity() {
+        // 0 → 1 の一方通行: 1からは0に戻れない
+        let graph: Graph = vec![vec![(1, 3)], vec![]];
+        let ecc = eccentricity(&graph);
+        assert_eq!(ecc, vec![Some(3), None]);
+        assert_eq!(diameter(&ecc), None);
+        assert_eq!(radius(&ecc), Some(3));
+    }
+}
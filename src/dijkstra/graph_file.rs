@@ -0,0 +1,116 @@
+use std::fs;
+
+use super::Graph;
+
+// =============================================================================
+// 📄 グラフファイルの読み込み
+// =============================================================================
+//
+// 1行に1本の辺を書くテキスト形式です。
+//
+//   # コメント行（#から始まる行と空行は無視）
+//   A B 5      … AとBを双方向に結ぶコスト5の道
+//   A -> D 3   … AからDへの一方通行（コスト3）
+//
+// ノード名は好きな文字列を使えます。出てきた順に0, 1, 2, ...の番号を振ります。
+
+// 名前付きのグラフ
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamedGraph {
+    pub graph: Graph,
+    pub names: Vec<String>,
+}
+
+impl NamedGraph {
+    // テキストからグラフを作る
+    // エラーの場合は行番号つきのメッセージを返す
+    pub fn parse(text: &str) -> Result<NamedGraph, String> {
+        let mut named = NamedGraph {
+            graph: Vec::new(),
+            names: Vec::new(),
+        };
+
+        for (line_no, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let (from, to, cost, directed) = match parts.as_slice() {
+                [from, to, cost] => (*from, *to, *cost, false),
+                [from, "->", to, cost] => (*from, *to, *cost, true),
+                _ => {
+                    return Err(format!(
+                        "{}行目: 'A B 5' または 'A -> B 5' の形式で書いてください: {}",
+                        line_no + 1,
+                        line
+                    ))
+                }
+            };
+            let cost: u32 = cost
+                .parse()
+                .map_err(|e| format!("{}行目: コストのパースに失敗しました: {}", line_no + 1, e))?;
+            // u32::MAX はダイクストラ法で「到達不可能」を表すので、辺のコストには使えない
+            if cost == u32::MAX {
+                return Err(format!(
+                    "{}行目: コストは{}以下にしてください",
+                    line_no + 1,
+                    u32::MAX - 1
+                ));
+            }
+
+            let from = named.intern(from);
+            let to = named.intern(to);
+            named.graph[from].push((to, cost));
+            if !directed {
+                named.graph[to].push((from, cost));
+            }
+        }
+
+        Ok(named)
+    }
+
+    // ファイルからグラフを読み込む
+    pub fn load(path: &str) -> Result<NamedGraph, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("ファイルを開けません({}): {}", path, e))?;
+        NamedGraph::parse(&text)
+    }
+
+    // ノード名から番号を探す
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|n| n == name)
+    }
+
+    // 番号からノード名を取得する
+    pub fn name(&self, node: usize) -> &str {
+        &self.names[node]
+    }
+
+    // from → to の辺を全て取り除き、取り除いた辺のコストを返す
+    pub fn remove_edge(&mut self, from: usize, to: usize) -> Vec<u32> {
+        let mut removed = Vec::new();
+        self.graph[from].retain(|&(next, cost)| {
+            if next == to {
+                removed.push(cost);
+                false
+            } else {
+                true
+            }
+        });
+        removed
+    }
+
+    // 名前を番号に変換する（初めて出てきた名前なら新しく登録）
+    fn intern(&mut self, name: &str) -> usize {
+        match self.index_of(name) {
+            Some(index) => index,
+            None => {
+                self.names.push(name.to_string());
+                self.graph.push(Vec::new());
+                self.names.len() - 1
+            }
+        }
+    }
+}
@@ -0,0 +1,363 @@
+use std::ops::RangeInclusive;
+
+use super::Graph;
+use crate::rng::XorShift64;
+
+// =============================================================================
+// 🧪 ランダムなグラフの生成（テスト・ベンチマーク用）
+// =============================================================================
+//
+// 手書きの小さなグラフだけでは見つからないバグを探すために、
+// シード付きの乱数で色々な形のグラフを作ります。
+//
+// - erdos_renyi            … 各ノード対に確率pで辺を張る
+// - road_grid              … 格子状の道路網（ところどころ道が欠け、斜めの近道がある）
+// - scale_free             … 一部のハブに辺が集中する（Barabási–Albertモデル）
+// - random_dag             … 閉路のない有向グラフ
+// - planted_negative_cycle … 負の閉路を1つ埋め込んだ有向グラフ
+//
+// 辺の重みは weights の範囲（両端を含む）から一様に選びます。
+// 負の重みも表せるように、辺は練習問題5と同じ (from, to, weight) の形で持ちます。
+
+pub type Edge = (usize, usize, i32);
+
+// 生成したグラフ（無向グラフの辺は1本だけ記録する）
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneratedGraph {
+    pub node_count: usize,
+    pub edges: Vec<Edge>,
+    pub directed: bool,
+}
+
+impl GeneratedGraph {
+    // 符号付きの隣接リストに変換する（無向グラフは両方向に辺を張る）
+    pub fn to_signed_adjacency(&self) -> Vec<Vec<(usize, i32)>> {
+        let mut adjacency = vec![Vec::new(); self.node_count];
+        for &(from, to, weight) in &self.edges {
+            adjacency[from].push((to, weight));
+            if !self.directed {
+                adjacency[to].push((from, weight));
+            }
+        }
+        adjacency
+    }
+
+    // ダイクストラ法で使う Graph に変換する
+    // 負の重みがあるとダイクストラ法は正しく動かないのでエラーにする
+    pub fn to_graph(&self) -> Result<Graph, String> {
+        if let Some(&(from, to, weight)) = self.edges.iter().find(|e| e.2 < 0) {
+            return Err(format!(
+                "負の重みの辺があります: {} → {} ({})",
+                from, to, weight
+            ));
+        }
+        Ok(self
+            .to_signed_adjacency()
+            .into_iter()
+            .map(|edges| edges.into_iter().map(|(to, w)| (to, w as u32)).collect())
+            .collect())
+    }
+}
+
+fn weight(rng: &mut XorShift64, weights: &RangeInclusive<i32>) -> i32 {
+    rng.range_i32(*weights.start(), *weights.end())
+}
+
+// Erdős–Rényiグラフ G(n, p)
+// directed = true なら (i, j) と (j, i) を別々に判定する
+pub fn erdos_renyi(
+    rng: &mut XorShift64,
+    n: usize,
+    p: f64,
+    weights: RangeInclusive<i32>,
+    directed: bool,
+) -> GeneratedGraph {
+    let mut edges = Vec::new();
+    for i in 0..n {
+        let first = if directed { 0 } else { i + 1 };
+        for j in first..n {
+            if i != j && rng.chance(p) {
+                edges.push((i, j, weight(rng, &weights)));
+            }
+        }
+    }
+    GeneratedGraph {
+        node_count: n,
+        edges,
+        directed,
+    }
+}
+
+// 格子状の道路網（無向）
+// ノード番号は row * cols + col
+// keep: 上下左右の道が残る確率、diagonal: 斜めの近道ができる確率
+pub fn road_grid(
+    rng: &mut XorShift64,
+    rows: usize,
+    cols: usize,
+    keep: f64,
+    diagonal: f64,
+    weights: RangeInclusive<i32>,
+) -> GeneratedGraph {
+    let mut edges = Vec::new();
+    let id = |r: usize, c: usize| r * cols + c;
+    for r in 0..rows {
+        for c in 0..cols {
+            if c + 1 < cols && rng.chance(keep) {
+                edges.push((id(r, c), id(r, c + 1), weight(rng, &weights)));
+            }
+            if r + 1 < rows && rng.chance(keep) {
+                edges.push((id(r, c), id(r + 1, c), weight(rng, &weights)));
+            }
+            if r + 1 < rows && c + 1 < cols && rng.chance(diagonal) {
+                edges.push((id(r, c), id(r + 1, c + 1), weight(rng, &weights)));
+            }
+        }
+    }
+    GeneratedGraph {
+        node_count: rows * cols,
+        edges,
+        directed: false,
+    }
+}
+
+// スケールフリーグラフ（Barabási–Albertモデル、無向）
+// 最初に m + 1 個のノードを全て結び、その後は1ノード追加するごとに
+// 次数に比例した確率で選んだ m 個の既存ノードとつなぐ
+pub fn scale_free(
+    rng: &mut XorShift64,
+    n: usize,
+    m: usize,
+    weights: RangeInclusive<i32>,
+) -> GeneratedGraph {
+    let m = m.max(1);
+    let mut edges = Vec::new();
+    // 辺の端点を全て並べたリスト（ここから一様に選ぶと次数に比例した選び方になる）
+    let mut endpoints = Vec::new();
+
+    let initial = n.min(m + 1);
+    for i in 0..initial {
+        for j in (i + 1)..initial {
+            edges.push((i, j, weight(rng, &weights)));
+            endpoints.push(i);
+            endpoints.push(j);
+        }
+    }
+
+    for new_node in initial..n {
+        let mut targets: Vec<usize> = Vec::with_capacity(m);
+        while targets.len() < m.min(new_node) {
+            let candidate = if endpoints.is_empty() {
+                rng.below(new_node)
+            } else {
+                endpoints[rng.below(endpoints.len())]
+            };
+            if !targets.contains(&candidate) {
+                targets.push(candidate);
+            }
+        }
+        for target in targets {
+            edges.push((target, new_node, weight(rng, &weights)));
+            endpoints.push(target);
+            endpoints.push(new_node);
+        }
+    }
+
+    GeneratedGraph {
+        node_count: n,
+        edges,
+        directed: false,
+    }
+}
+
+// 閉路のない有向グラフ（DAG）
+// ランダムなトポロジカル順序を決めて、順序の前から後ろへの辺だけを確率pで張る
+pub fn random_dag(
+    rng: &mut XorShift64,
+    n: usize,
+    p: f64,
+    weights: RangeInclusive<i32>,
+) -> GeneratedGraph {
+    let mut order: Vec<usize> = (0..n).collect();
+    rng.shuffle(&mut order);
+    let mut edges = Vec::new();
+    for i in 0..n {
+        for j in (i + 1)..n {
+            if rng.chance(p) {
+                edges.push((order[i], order[j], weight(rng, &weights)));
+            }
+        }
+    }
+    GeneratedGraph {
+        node_count: n,
+        edges,
+        directed: true,
+    }
+}
+
+// 負の閉路を1つ埋め込んだ有向グラフ
+// 有向のErdős–Rényiグラフに、cycle_len 個のノードを回る閉路を追加する
+// 閉路の最後の辺の重みを調整して、閉路全体の重みを負にする
+// 最後の辺がi32に収まるように、閉路の途中の辺の重みは合計が大きくなりすぎない範囲に切り詰める
+// （weights が広いときや閉路が長いときは、weights の範囲より小さい重みになることがある）
+// 返り値: (グラフ, 閉路のノード列)
+pub fn planted_negative_cycle(
+    rng: &mut XorShift64,
+    n: usize,
+    p: f64,
+    weights: RangeInclusive<i32>,
+    cycle_len: usize,
+) -> (GeneratedGraph, Vec<usize>) {
+    assert!(
+        cycle_len >= 2 && cycle_len <= n,
+        "閉路の長さは 2 以上 n 以下にしてください"
+    );
+    let mut graph = erdos_renyi(rng, n, p, weights.clone(), true);
+
+    let mut nodes: Vec<usize> = (0..n).collect();
+    rng.shuffle(&mut nodes);
+    let cycle: Vec<usize> = nodes[..cycle_len].to_vec();
+
+    // 途中の辺の合計をこの範囲に収めれば、最後の辺 -(合計 + 1 〜 10) もi32に収まる
+    let limit = i32::MAX as i64 - 10;
+    let mut total: i64 = 0;
+    for k in 0..cycle_len - 1 {
+        let w = (weight(rng, &weights) as i64).clamp(-limit - total, limit - total);
+        total += w;
+        graph.edges.push((cycle[k], cycle[k + 1], w as i32));
+    }
+    // 閉路の重みの合計が -1 〜 -10 になるように最後の辺を決める
+    let last = -(total + 1 + rng.below(10) as i64);
+    let last = i32::try_from(last).expect("閉路の最後の辺の重みがi32に収まりません");
+    graph.edges.push((cycle[cycle_len - 1], cycle[0], last));
+
+    (graph, cycle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dijkstra::dijkstra;
+
+    // 参照実装: ベルマン・フォード法
+    // 負の閉路に到達できる場合はNone
+    fn bellman_ford(graph: &GeneratedGraph, start: usize) -> Option<Vec<i64>> {
+        let adjacency = graph.to_signed_adjacency();
+        let n = graph.node_count;
+        let mut distances = vec![i64::MAX; n];
+        distances[start] = 0;
+        for round in 0..n {
+            let mut updated = false;
+            for from in 0..n {
+                if distances[from] == i64::MAX {
+                    continue;
+                }
+                for &(to, w) in &adjacency[from] {
+                    if distances[from] + (w as i64) < distances[to] {
+                        distances[to] = distances[from] + w as i64;
+                        updated = true;
+                    }
+                }
+            }
+            if !updated {
+                return Some(distances);
+            }
+            if round == n - 1 {
+                return None;
+            }
+        }
+        Some(distances)
+    }
+
+    fn assert_dijkstra_matches(graph: &GeneratedGraph) {
+        let adjacency = graph.to_graph().unwrap();
+        for start in 0..graph.node_count.min(5) {
+            let expected = bellman_ford(graph, start).unwrap();
+            let actual = dijkstra(&adjacency, start);
+            for (e, a) in expected.iter().zip(actual) {
+                if *e == i64::MAX {
+                    assert_eq!(a, u32::MAX);
+                } else {
+                    assert_eq!(a as i64, *e);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_same_seed_same_graph() {
+        let a = erdos_renyi(&mut XorShift64::new(7), 20, 0.3, 1..=9, true);
+        let b = erdos_renyi(&mut XorShift64::new(7), 20, 0.3, 1..=9, true);
+        let c = erdos_renyi(&mut XorShift64::new(8), 20, 0.3, 1..=9, true);
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn test_fuzz_dijkstra_against_bellman_ford() {
+        for seed in 0..20 {
+            let mut rng = XorShift64::new(seed);
+            assert_dijkstra_matches(&erdos_renyi(&mut rng, 30, 0.1, 0..=20, seed % 2 == 0));
+            assert_dijkstra_matches(&road_grid(&mut rng, 6, 7, 0.8, 0.2, 1..=9));
+            assert_dijkstra_matches(&scale_free(&mut rng, 40, 2, 1..=50));
+            assert_dijkstra_matches(&random_dag(&mut rng, 25, 0.2, 0..=10));
+        }
+    }
+
+    #[test]
+    fn test_dag_has_no_cycle() {
+        let graph = random_dag(&mut XorShift64::new(3), 30, 0.3, -5..=5);
+        // カーンのアルゴリズムで全ノードを取り出せればDAG
+        let mut indegree = vec![0; graph.node_count];
+        for &(_, to, _) in &graph.edges {
+            indegree[to] += 1;
+        }
+        let adjacency = graph.to_signed_adjacency();
+        let mut stack: Vec<usize> = (0..graph.node_count)
+            .filter(|&v| indegree[v] == 0)
+            .collect();
+        let mut removed = 0;
+        while let Some(v) = stack.pop() {
+            removed += 1;
+            for &(to, _) in &adjacency[v] {
+                indegree[to] -= 1;
+                if indegree[to] == 0 {
+                    stack.push(to);
+                }
+            }
+        }
+        assert_eq!(removed, graph.node_count);
+    }
+
+    #[test]
+    fn test_planted_negative_cycle_is_detected() {
+        for seed in 0..10 {
+            let mut rng = XorShift64::new(seed);
+            let (graph, cycle) = planted_negative_cycle(&mut rng, 15, 0.15, 1..=20, 4);
+            assert!(graph.to_graph().is_err());
+            // 閉路上のノードから始めれば必ず負の閉路に到達する
+            assert_eq!(bellman_ford(&graph, cycle[0]), None);
+        }
+    }
+
+    #[test]
+    fn test_planted_cycle_with_huge_weights_stays_negative() {
+        // 途中の辺だけで i32 の範囲を超える重み
+        for seed in 0..10 {
+            let mut rng = XorShift64::new(seed);
+            let (graph, cycle) = planted_negative_cycle(&mut rng, 12, 0.2, 0..=i32::MAX / 2, 10);
+            // 閉路の辺は最後に追加した cycle.len() 本
+            let cycle_edges = &graph.edges[graph.edges.len() - cycle.len()..];
+            let total: i64 = cycle_edges.iter().map(|&(_, _, w)| w as i64).sum();
+            assert!((-10..=-1).contains(&total), "{}", total);
+            assert_eq!(bellman_ford(&graph, cycle[0]), None);
+        }
+    }
+
+    #[test]
+    fn test_scale_free_edge_count() {
+        let graph = scale_free(&mut XorShift64::new(1), 50, 3, 1..=1);
+        // 最初の4ノードの完全グラフ(6本) + 残り46ノード × 3本
+        assert_eq!(graph.edges.len(), 6 + 46 * 3);
+    }
+}
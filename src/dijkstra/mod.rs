@@ -0,0 +1,367 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::trace::{NoTrace, SearchObserver};
+
+// =============================================================================
+// 📦 ダイクストラ法: 共通の実装
+// =============================================================================
+//
+// 配送ネットワークのデモ（src/main.rs）や応用モジュールから共有して使う
+// 基本のダイクストラ法と経路復元です。
+
+pub mod centrality;
+pub mod graph_file;
+pub mod graph_gen;
+pub mod repl;
+pub mod route_planner;
+
+pub type Graph = Vec<Vec<(usize, u32)>>;
+
+// ダイクストラ法の実装（基本版）
+pub fn dijkstra(graph: &Graph, start: usize) -> Vec<u32> {
+    dijkstra_traced(graph, start, &mut NoTrace)
+}
+
+// トレース付きのダイクストラ法
+// observerにヒープの取り出し・スキップ・確定・距離の更新を通知する
+pub fn dijkstra_traced<O: SearchObserver<usize>>(
+    graph: &Graph,
+    start: usize,
+    observer: &mut O,
+) -> Vec<u32> {
+    // グラフのノード数を取得（例：5個のノードA,B,C,D,E）
+    let n = graph.len();
+
+    // 各ノードへの最短距離を記録する配列を作成
+    // 初期値はu32::MAX（無限大の代わり）で埋める
+    // distances[0]=∞, distances[1]=∞, ..., distances[4]=∞
+    let mut distances = vec![u32::MAX; n];
+
+    // 優先度付きキュー（最小ヒープとして使用）
+    // Reverseで包むことで、コストが小さい順に取り出せる
+    let mut heap = BinaryHeap::new();
+
+    // 始点（start）への距離は0に設定
+    // 例：start=0(A)なら、distances[0] = 0
+    distances[start] = 0;
+
+    // ヒープに始点を追加
+    // Reverse((コスト0, ノード番号start))の形で追加
+    // 例：Reverse((0, 0)) → コスト0でノードAを追加
+    heap.push(Reverse((0, start)));
+
+    // ヒープが空になるまで処理を繰り返す
+    while let Some(Reverse((cost, node))) = heap.pop() {
+        observer.on_pop(node, cost as u64);
+
+        // 【重要な最適化】
+        // もし取り出したコストが、既に記録されている最短距離より大きければスキップ
+        // （同じノードが異なるコストで複数回ヒープに入る可能性があるため）
+        // 例：distances[1]=5なのに、cost=10のB(1)が出てきたらスキップ
+        if cost > distances[node] {
+            observer.on_skip_stale(node, cost as u64);
+            continue;
+        }
+        observer.on_visit(node, cost as u64);
+
+        // 現在のノードから行ける全ての隣接ノードをチェック
+        // graph[node]には [(隣接ノード, エッジのコスト), ...] が入っている
+        // 例：node=0(A)なら、graph[0] = [(1, 5), (2, 2)] → Bへ5、Cへ2
+        for &(next_node, edge_cost) in &graph[node] {
+            // 新しいコスト = 現在のノードまでのコスト + エッジのコスト
+            // 例：A(cost=0) → B(edge_cost=5) なら new_cost = 0 + 5 = 5
+            // u32に収まらない（あふれる）場合は、到達できないものとして扱う
+            let Some(new_cost) = cost.checked_add(edge_cost) else {
+                continue;
+            };
+
+            // もし新しいコストが、記録されている距離より小さければ更新
+            // 例：distances[1]=∞ で new_cost=5 なら更新する
+            if new_cost < distances[next_node] {
+                // 最短距離を更新
+                distances[next_node] = new_cost;
+                observer.on_relax(node, next_node, new_cost as u64);
+
+                // ヒープに新しいコストでこのノードを追加
+                // 例：Reverse((5, 1)) → コスト5でノードBを追加
+                heap.push(Reverse((new_cost, next_node)))
+            }
+        }
+    }
+
+    // 全ノードへの最短距離の配列を返す
+    // 例：[0, 5, 2, 3, 6] → A:0, B:5, C:2, D:3, E:6
+    distances
+}
+
+// 経路復元機能付きダイクストラ法
+pub fn dijkstra_with_path(graph: &Graph, start: usize) -> (Vec<u32>, Vec<Option<usize>>) {
+    // グラフのノード数を取得
+    let n = graph.len();
+
+    // 各ノードへの最短距離を記録する配列（初期値は無限大）
+    let mut distances = vec![u32::MAX; n];
+
+    // 【重要】各ノードに「どのノードから来たか」を記録する配列
+    // previous[i] = Some(j) → ノードiにはノードjから来た
+    // previous[i] = None → ノードiは未到達または始点
+    // 例：previous = [None, Some(0), Some(0), Some(2), Some(2)]
+    //     → A:始点, B:Aから, C:Aから, D:Cから, E:Cから
+    let mut previous: Vec<Option<usize>> = vec![None; n];
+
+    // 優先度付きキュー（最小ヒープ）
+    let mut heap = BinaryHeap::new();
+
+    // 始点の距離を0に設定
+    distances[start] = 0;
+
+    // ヒープに始点を追加
+    // Reverse((0, 0)) → コスト0でノードAを追加
+    heap.push(Reverse((0, start)));
+
+    // ヒープが空になるまで処理
+    while let Some(Reverse((cost, node))) = heap.pop() {
+        // 既に処理済みならスキップ
+        // （同じノードが複数回ヒープに入る可能性があるため）
+        if cost > distances[node] {
+            continue;
+        }
+
+        // 現在のノードから行ける全ての隣接ノードをチェック
+        for &(next_node, edge_cost) in &graph[node] {
+            // 新しいコストを計算
+            // 例：C(cost=2) → D(edge_cost=1) なら new_cost = 2 + 1 = 3
+            // あふれる場合は到達できないものとして扱う
+            let Some(new_cost) = cost.checked_add(edge_cost) else {
+                continue;
+            };
+
+            // より短い経路が見つかったら更新
+            if new_cost < distances[next_node] {
+                // 最短距離を更新
+                distances[next_node] = new_cost;
+
+                // 【重要】どこから来たかを記録
+                // 例：ノードD(3)に、ノードC(2)から来た場合
+                //     previous[3] = Some(2)
+                previous[next_node] = Some(node);
+
+                // ヒープに新しいコストでこのノードを追加
+                heap.push(Reverse((new_cost, next_node)));
+            }
+        }
+    }
+
+    // 最短距離の配列と、経路復元用の配列を両方返す
+    // 例：([0, 5, 2, 3, 6], [None, Some(0), Some(0), Some(2), Some(2)])
+    //     distances: A=0, B=5, C=2, D=3, E=6
+    //     previous: A=始点, B=Aから, C=Aから, D=Cから, E=Cから
+    (distances, previous)
+}
+
+// 経路を復元する関数
+pub fn reconstruct_path(previous: &[Option<usize>], start: usize, end: usize) -> Vec<usize> {
+    // 経路を格納するベクター
+    let mut path = Vec::new();
+
+    // 現在のノード（最初は終点から開始）
+    let mut current = end;
+
+    // 終点から始点まで逆向きに辿る
+    // 例：A(0)→D(3)の経路を復元する場合
+    //     previous = [None, Some(0), Some(0), Some(2), ...]
+    //     D(3)はC(2)から来た、C(2)はA(0)から来た
+    while current != start {
+        // 現在のノードを経路に追加
+        // 1回目：path = [3] (D)
+        // 2回目：path = [3, 2] (D, C)
+        path.push(current);
+
+        // previous配列から「どこから来たか」を取得
+        match previous[current] {
+            // Some(prev)の場合：prevノードから来た
+            // 例：previous[3] = Some(2) → D(3)はC(2)から来た
+            Some(prev) => {
+                // currentをprevに更新して、さらに遡る
+                // 1回目：current = 3 → 2
+                // 2回目：current = 2 → 0
+                current = prev;
+            }
+            // Noneの場合：経路が存在しない（到達不可能）
+            None => return vec![],
+        }
+    }
+
+    // 最後に始点を追加
+    // path = [3, 2, 0] (D, C, A)
+    path.push(start);
+
+    // 逆順になっているので正順に直す
+    // path = [3, 2, 0] → [0, 2, 3] (A, C, D)
+    path.reverse();
+
+    // 完成した経路を返す
+    // 例：[0, 2, 3] → A→C→Dの経路
+    path
+}
+
+// 1つの始点から見た最短経路の本数（練習問題3、媒介中心性で共有する）
+pub struct ShortestPathCounts {
+    pub distances: Vec<u32>,
+    // path_count[w] = 始点からwへの最短経路の本数（大きくなりすぎたらusize::MAXで止める）
+    pub path_count: Vec<usize>,
+    // predecessors[w] = 最短経路でwの直前に来るノードの一覧
+    pub predecessors: Vec<Vec<usize>>,
+    // 距離が確定した順番に並べたノード
+    pub order: Vec<usize>,
+}
+
+// 経路の本数を数えながらダイクストラ法を実行する
+// 既に確定したノードへの辺は見ない。こうすると
+//   - ノードが確定した時点で、その本数と直前のノードの一覧はもう変わらない
+//   - 直前のノードは必ず先に確定している（order は最短経路のDAGの順番になる）
+// ので、コスト0の辺があっても数え方が崩れない。
+// コスト0の辺でつながった同じ距離のノードどうしは、先に確定した方から後の方への経路だけを数える
+// （コスト0の閉路があっても、経路の本数が無限にならない）
+pub fn count_shortest_paths(graph: &Graph, start: usize) -> ShortestPathCounts {
+    let n = graph.len();
+    let mut settled = vec![false; n];
+    let mut distances = vec![u32::MAX; n];
+    let mut path_count: Vec<usize> = vec![0; n];
+    let mut predecessors = vec![Vec::new(); n];
+    let mut order = Vec::with_capacity(n);
+    let mut heap: BinaryHeap<Reverse<(u32, usize)>> = BinaryHeap::new();
+
+    distances[start] = 0;
+    path_count[start] = 1; // 始点への経路は1通り
+    heap.push(Reverse((0, start)));
+
+    while let Some(Reverse((cost, node))) = heap.pop() {
+        if settled[node] {
+            continue;
+        }
+        settled[node] = true;
+        order.push(node);
+
+        for &(next_node, edge_cost) in &graph[node] {
+            if settled[next_node] {
+                continue;
+            }
+            // u32に収まらないほど遠いノード（u32::MAX以上）は到達できないものとして扱う
+            let Some(new_cost) = cost.checked_add(edge_cost).filter(|&c| c < u32::MAX) else {
+                continue;
+            };
+
+            if new_cost < distances[next_node] {
+                // より短い経路: 本数をコピーし、直前のノードをリセット
+                distances[next_node] = new_cost;
+                path_count[next_node] = path_count[node];
+                predecessors[next_node] = vec![node];
+                heap.push(Reverse((new_cost, next_node)));
+            } else if new_cost == distances[next_node] {
+                // 同じコストの経路: 本数を加算
+                path_count[next_node] = path_count[next_node].saturating_add(path_count[node]);
+                predecessors[next_node].push(node);
+            }
+        }
+    }
+
+    ShortestPathCounts {
+        distances,
+        path_count,
+        predecessors,
+        order,
+    }
+}
+
+// 最短経路の本数を数える（練習問題3）
+pub fn practice3_count_shortest_paths(graph: &Graph, start: usize, end: usize) -> usize {
+    count_shortest_paths(graph, start).path_count[end]
+}
+
+// ホップ数制限付きの最短経路（練習問題4）
+pub fn practice4_limited_hops(
+    graph: &Graph,
+    start: usize,
+    end: usize,
+    max_hops: usize,
+) -> Option<u32> {
+    // グラフのノード数を取得（例：6個の都市）
+    let n = graph.len();
+
+    // 同じノードを2度通らない経路は最大でも n - 1 ホップなので、それより大きいKは切り詰める
+    // （巨大なKのまま配列を作るとメモリが足りなくなる）
+    let max_hops = max_hops.min(n.saturating_sub(1));
+
+    // 2次元配列を作成: distances[ノード番号][ホップ数] = そのノードにそのホップ数で到達する最小コスト
+    // max_hops=2なら、[0ホップ, 1ホップ, 2ホップ]の3つ分の領域が必要なので max_hops+1
+    // 例: distances[3][1] = ノード3に1ホップで到達する最小コスト
+    let mut distances = vec![vec![u32::MAX; max_hops + 1]; n];
+
+    // 優先度付きキュー: (コスト, ノード番号, ホップ数) のタプルを格納
+    // Reverseで包むことで、コストが小さい順に取り出せる
+    let mut heap: BinaryHeap<Reverse<(u32, usize, usize)>> = BinaryHeap::new();
+
+    // 始点への0ホップでのコストは0（移動なし、自分自身）
+    // 例: start=0なら、distances[0][0] = 0
+    distances[start][0] = 0;
+
+    // ヒープに始点を追加: (コスト0, 始点ノード, 0ホップ)
+    // 例: Reverse((0, 0, 0)) → 東京から0ホップで東京に到達
+    heap.push(Reverse((0, start, 0)));
+
+    // ヒープが空になるまで処理を繰り返す
+    while let Some(Reverse((cost, node, hop_count))) = heap.pop() {
+        // ホップ数が制限を超えている場合はスキップ
+        // 例: max_hops=2でhop_count=3ならスキップ（3ホップは許可されない）
+        if hop_count > max_hops {
+            continue;
+        }
+
+        // 既により良い経路が見つかっている場合はスキップ
+        // 例: distances[3][1]=50 で cost=60 ならスキップ（既に良い経路がある）
+        if cost > distances[node][hop_count] {
+            continue;
+        }
+
+        // 終点に到達したら、そのコストを返す
+        // 例: node=5(神戸)でend=5なら、現在のコストを返す
+        if node == end {
+            return Some(cost);
+        }
+
+        // まだホップ数に余裕がある場合のみ、隣接ノードを探索
+        // 例: hop_count=1, max_hops=2 なら、あと1ホップできる
+        if hop_count < max_hops {
+            // 現在のノードから行ける全ての隣接ノードをチェック
+            // 例: node=0(東京)なら、横浜と大阪をチェック
+            for &(next_node, edge_cost) in &graph[node] {
+                // 新しいコスト = 現在までのコスト + エッジのコスト
+                // 例: cost=30(東京→横浜) + edge_cost=20(横浜→京都) = 50
+                // あふれる場合は到達できないものとして扱う
+                let Some(new_cost) = cost.checked_add(edge_cost) else {
+                    continue;
+                };
+
+                // 次のホップ数 = 現在のホップ数 + 1
+                // 例: hop_count=1 → next_hop=2（2ホップ目）
+                let next_hop = hop_count + 1;
+
+                // より良い経路が見つかった場合のみ更新
+                // 例: distances[3][2]=100 で new_cost=50 なら更新
+                if new_cost < distances[next_node][next_hop] {
+                    // 最短距離を更新
+                    distances[next_node][next_hop] = new_cost;
+
+                    // ヒープに新しい状態を追加
+                    // 例: Reverse((50, 3, 2)) → コスト50で京都に2ホップで到達
+                    heap.push(Reverse((new_cost, next_node, next_hop)));
+                }
+            }
+        }
+    }
+
+    // ヒープが空になっても終点に到達できなかった場合はNone
+    None
+}
@@ -0,0 +1,307 @@
+use std::io::{self, BufRead, Write};
+
+use super::graph_file::NamedGraph;
+use super::{
+    dijkstra_with_path, practice3_count_shortest_paths, practice4_limited_hops, reconstruct_path,
+};
+
+// =============================================================================
+// 💬 配送ネットワークの対話モード（REPL）
+// =============================================================================
+//
+// グラフファイルを読み込んで、コマンドを1行ずつ入力して経路を調べます。
+//
+//   path B E     … BからEへの最短経路とコスト
+//   dist A *     … Aから全ノードへの最短距離（* の代わりにノード名も可）
+//   block C D    … CとDを結ぶ道を通行止めにする
+//   unblock C D  … 通行止めを解除する
+//   hops A E 2   … 最大2ホップでAからEへ行く最小コスト
+//   count A D    … AからDへの最短経路の本数
+//   help / quit
+//
+// readlineなどの外部クレートは使わず、標準入力を1行ずつ読みます。
+// 端末がバックスペースを文字のまま送ってきた場合も edit_line で反映します。
+
+const HELP: &str = "\
+コマンド一覧:
+  path <始点> <終点>        最短経路とコスト
+  dist <始点> <終点|*>      最短距離（* で全ノード）
+  block <A> <B>             AとBの間の道を通行止めにする
+  unblock <A> <B>           通行止めを解除する
+  hops <始点> <終点> <K>    最大Kホップでの最小コスト
+  count <始点> <終点>       最短経路の本数
+  help                      このヘルプを表示
+  quit                      終了";
+
+// 1行分の入力を整える
+// バックスペース（\x08）とDEL（\x7f）は直前の1文字を消し、他の制御文字は捨てる
+pub fn edit_line(raw: &str) -> String {
+    let mut line = String::new();
+    for ch in raw.chars() {
+        match ch {
+            '\u{8}' | '\u{7f}' => {
+                line.pop();
+            }
+            c if c.is_control() => {}
+            c => line.push(c),
+        }
+    }
+    line.trim().to_string()
+}
+
+// REPLの状態（読み込んだグラフと通行止めにした道）
+pub struct RouteRepl {
+    named: NamedGraph,
+    // 通行止めにした辺: (from, to, コスト)
+    blocked: Vec<(usize, usize, u32)>,
+}
+
+impl RouteRepl {
+    pub fn new(named: NamedGraph) -> RouteRepl {
+        RouteRepl {
+            named,
+            blocked: Vec::new(),
+        }
+    }
+
+    // ノード名を番号に変換する
+    fn node(&self, name: &str) -> Result<usize, String> {
+        self.named
+            .index_of(name)
+            .ok_or_else(|| format!("ノード '{}' は存在しません", name))
+    }
+
+    // 経路を "A → C → E" の形にする
+    fn format_path(&self, path: &[usize]) -> String {
+        path.iter()
+            .map(|&node| self.named.name(node))
+            .collect::<Vec<_>>()
+            .join(" → ")
+    }
+
+    // コマンドを1つ実行して、表示する文字列を返す
+    pub fn execute(&mut self, line: &str) -> Result<String, String> {
+        let args: Vec<&str> = line.split_whitespace().collect();
+        match args.as_slice() {
+            ["help"] => Ok(HELP.to_string()),
+            ["path", from, to] => {
+                let (from, to) = (self.node(from)?, self.node(to)?);
+                let (distances, previous) = dijkstra_with_path(&self.named.graph, from);
+                if distances[to] == u32::MAX {
+                    return Ok(format!("{} → {}: 到達不可能", args[1], args[2]));
+                }
+                let path = reconstruct_path(&previous, from, to);
+                Ok(format!(
+                    "{} (コスト: {})",
+                    self.format_path(&path),
+                    distances[to]
+                ))
+            }
+            ["dist", from, to] => {
+                let from = self.node(from)?;
+                let (distances, _) = dijkstra_with_path(&self.named.graph, from);
+                let targets: Vec<usize> = if *to == "*" {
+                    (0..distances.len()).collect()
+                } else {
+                    vec![self.node(to)?]
+                };
+                let lines: Vec<String> = targets
+                    .into_iter()
+                    .map(|target| {
+                        let dist = match distances[target] {
+                            u32::MAX => "到達不可能".to_string(),
+                            d => d.to_string(),
+                        };
+                        format!("{} → {}: {}", args[1], self.named.name(target), dist)
+                    })
+                    .collect();
+                Ok(lines.join("\n"))
+            }
+            ["block", a, b] => {
+                let (a, b) = (self.node(a)?, self.node(b)?);
+                let mut count = 0;
+                for (from, to) in [(a, b), (b, a)] {
+                    for cost in self.named.remove_edge(from, to) {
+                        self.blocked.push((from, to, cost));
+                        count += 1;
+                    }
+                }
+                if count == 0 {
+                    return Err(format!("{} と {} の間に道はありません", args[1], args[2]));
+                }
+                Ok(format!(
+                    "{} - {} を通行止めにしました（{}本）",
+                    args[1], args[2], count
+                ))
+            }
+            ["unblock", a, b] => {
+                let (a, b) = (self.node(a)?, self.node(b)?);
+                let before = self.blocked.len();
+                let graph = &mut self.named.graph;
+                self.blocked.retain(|&(from, to, cost)| {
+                    let matches = (from, to) == (a, b) || (from, to) == (b, a);
+                    if matches {
+                        graph[from].push((to, cost));
+                    }
+                    !matches
+                });
+                if self.blocked.len() == before {
+                    return Err(format!(
+                        "{} - {} は通行止めになっていません",
+                        args[1], args[2]
+                    ));
+                }
+                Ok(format!(
+                    "{} - {} の通行止めを解除しました",
+                    args[1], args[2]
+                ))
+            }
+            ["hops", from, to, max_hops] => {
+                let (from, to) = (self.node(from)?, self.node(to)?);
+                let max_hops: usize = match max_hops.parse() {
+                    Ok(0) | Err(_) => {
+                        return Err(format!(
+                            "ホップ数は1以上の整数で指定してください: {}",
+                            args[3]
+                        ))
+                    }
+                    Ok(k) => k,
+                };
+                match practice4_limited_hops(&self.named.graph, from, to, max_hops) {
+                    Some(cost) => Ok(format!(
+                        "{} → {} (最大{}ホップ): {}",
+                        args[1], args[2], max_hops, cost
+                    )),
+                    None => Ok(format!(
+                        "{} → {} (最大{}ホップ): 条件を満たす経路なし",
+                        args[1], args[2], max_hops
+                    )),
+                }
+            }
+            ["count", from, to] => {
+                let (from, to) = (self.node(from)?, self.node(to)?);
+                let count = practice3_count_shortest_paths(&self.named.graph, from, to);
+                Ok(format!(
+                    "{} → {} の最短経路: {}通り",
+                    args[1], args[2], count
+                ))
+            }
+            [] => Ok(String::new()),
+            _ => Err(format!(
+                "コマンドを解釈できません: '{}'（help で一覧を表示）",
+                line
+            )),
+        }
+    }
+
+    // 入力が尽きるか quit が来るまでコマンドを読み続ける
+    pub fn run<R: BufRead, W: Write>(&mut self, input: R, output: &mut W) -> io::Result<()> {
+        write!(output, "> ")?;
+        output.flush()?;
+        for raw in input.lines() {
+            let line = edit_line(&raw?);
+            if line == "quit" || line == "exit" {
+                break;
+            }
+            match self.execute(&line) {
+                Ok(text) if text.is_empty() => {}
+                Ok(text) => writeln!(output, "{}", text)?,
+                Err(message) => writeln!(output, "エラー: {}", message)?,
+            }
+            write!(output, "> ")?;
+            output.flush()?;
+        }
+        writeln!(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DELIVERY: &str = "\
+A B 5
+A C 2
+B C 3
+B D 7
+C D 1
+C E 4
+D E 6
+";
+
+    #[test]
+    fn test_repl_commands() {
+        let mut repl = RouteRepl::new(NamedGraph::parse(DELIVERY).unwrap());
+        assert_eq!(repl.execute("path B E").unwrap(), "B → C → E (コスト: 7)");
+        assert_eq!(repl.execute("dist A E").unwrap(), "A → E: 6");
+        assert_eq!(
+            repl.execute("count A D").unwrap(),
+            "A → D の最短経路: 1通り"
+        );
+        assert_eq!(
+            repl.execute("hops A E 1").unwrap(),
+            "A → E (最大1ホップ): 条件を満たす経路なし"
+        );
+
+        repl.execute("block C E").unwrap();
+        assert_eq!(
+            repl.execute("path B E").unwrap(),
+            "B → C → D → E (コスト: 10)"
+        );
+        repl.execute("unblock C E").unwrap();
+        assert_eq!(repl.execute("path B E").unwrap(), "B → C → E (コスト: 7)");
+
+        assert!(repl.execute("path A Z").is_err());
+        assert!(repl.execute("fly A B").is_err());
+    }
+
+    #[test]
+    fn test_hops_rejects_or_clamps_bad_k() {
+        let mut repl = RouteRepl::new(NamedGraph::parse(DELIVERY).unwrap());
+        assert!(repl.execute("hops A E 0").is_err());
+        assert!(repl.execute("hops A E -1").is_err());
+        assert!(repl.execute("hops A E 99999999999999999999").is_err());
+        // ノード数より大きいKは n - 1 ホップに切り詰めて計算する
+        assert_eq!(
+            repl.execute("hops A E 18446744073709551615").unwrap(),
+            "A → E (最大18446744073709551615ホップ): 6"
+        );
+    }
+
+    #[test]
+    fn test_huge_costs_do_not_overflow() {
+        // u32::MAX は「到達不可能」の印なので、辺のコストには使えない
+        assert!(NamedGraph::parse("A B 4294967295").is_err());
+        // 合計がu32に収まらない経路は到達できないものとして扱う
+        let mut repl = RouteRepl::new(NamedGraph::parse("A B 4294967294\nB C 1").unwrap());
+        assert_eq!(
+            repl.execute("path A B").unwrap(),
+            "A → B (コスト: 4294967294)"
+        );
+        assert_eq!(repl.execute("path A C").unwrap(), "A → C: 到達不可能");
+        assert_eq!(
+            repl.execute("count A C").unwrap(),
+            "A → C の最短経路: 0通り"
+        );
+        assert!(repl
+            .execute("hops A C 3")
+            .unwrap()
+            .ends_with("条件を満たす経路なし"));
+    }
+
+    #[test]
+    fn test_edit_line_applies_backspace() {
+        assert_eq!(edit_line("paht\u{8}\u{8}th A B\r"), "path A B");
+    }
+
+    #[test]
+    fn test_run_reads_until_quit() {
+        let mut repl = RouteRepl::new(NamedGraph::parse(DELIVERY).unwrap());
+        let mut output = Vec::new();
+        repl.run("dist A *\nquit\npath A E\n".as_bytes(), &mut output)
+            .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("A → D: 3"));
+        assert!(!output.contains("コスト"));
+    }
+}
@@ -0,0 +1,398 @@
+use super::{dijkstra_with_path, reconstruct_path, Graph};
+
+// =============================================================================
+// 🚚 複数拠点の配送ルート計画（巡回セールスマン問題）
+// =============================================================================
+//
+// ドライバーは1回の配送で複数の配送センターを回ります。
+// 1. 訪問先どうしの距離行列をダイクストラ法で作る
+// 2. 訪問先が少なければ Held-Karp（ビットDP）で厳密解を求める
+// 3. 多ければ 最近傍法 + 2-opt で近似解を求める
+// 4. 訪問順を元のグラフ上の経路（ノード列）に展開する
+//
+// 例: A(0)から B(1), D(3), E(4) を回ってAに戻る
+//     B, D, E の回り方は 3! = 6 通りあり、その中で合計コストが最小の順番を探す
+
+// Held-Karpで厳密に解く地点数の上限（デポと訪問先を合わせた数）
+// 計算量は O(2^n * n^2) なので、これより多い場合は近似解に切り替える
+pub const HELD_KARP_LIMIT: usize = 13;
+
+// 到達不可能を表す合計コスト（合計はu64で足すのでu64::MAX）
+// 距離行列の1区間はダイクストラ法と同じくu32::MAXを無限大として使う
+const INF: u64 = u64::MAX;
+
+// ルート計画の結果
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoutePlan {
+    // 訪問するノードの順番（先頭はデポ、周回なら末尾もデポ）
+    pub order: Vec<usize>,
+    // 元のグラフ上で実際にたどるノード列
+    pub path: Vec<usize>,
+    // 合計コスト
+    pub cost: u64,
+    // Held-Karpで求めた厳密解ならtrue
+    pub exact: bool,
+}
+
+// 訪問先どうしの距離行列を作る
+// matrix[i][j] = stops[i] から stops[j] への最短距離（到達不可能ならu32::MAX）
+// 経路の展開に使うため、各始点のprevious配列も一緒に返す
+pub fn distance_matrix(graph: &Graph, stops: &[usize]) -> (Vec<Vec<u32>>, Vec<Vec<Option<usize>>>) {
+    let mut matrix = Vec::with_capacity(stops.len());
+    let mut previous_list = Vec::with_capacity(stops.len());
+
+    for &from in stops {
+        let (distances, previous) = dijkstra_with_path(graph, from);
+        matrix.push(stops.iter().map(|&to| distances[to]).collect());
+        previous_list.push(previous);
+    }
+
+    (matrix, previous_list)
+}
+
+// 距離行列上の経路コストを計算する（到達不可能な区間があればINF）
+fn tour_cost(matrix: &[Vec<u32>], tour: &[usize]) -> u64 {
+    let mut total: u64 = 0;
+    for pair in tour.windows(2) {
+        let edge = matrix[pair[0]][pair[1]];
+        if edge == u32::MAX {
+            return INF;
+        }
+        total += edge as u64;
+    }
+    total
+}
+
+// Held-Karp（ビットDP）による厳密解
+// dp[mask][last] = 「maskの訪問先を回ってlastにいる」ときの最小コスト
+// 0番（デポ）から出発し、round_tripならデポに戻る
+// 返り値: (距離行列上の訪問順, 合計コスト)
+pub fn held_karp(matrix: &[Vec<u32>], round_trip: bool) -> Option<(Vec<usize>, u64)> {
+    let n = matrix.len();
+    if n == 0 {
+        return None;
+    }
+    if n == 1 {
+        return Some((if round_trip { vec![0, 0] } else { vec![0] }, 0));
+    }
+
+    let full = 1usize << n;
+    let mut dp = vec![vec![INF; n]; full];
+    let mut parent = vec![vec![usize::MAX; n]; full];
+    dp[1][0] = 0;
+
+    for mask in 1..full {
+        // デポを含まない状態は存在しない
+        if mask & 1 == 0 {
+            continue;
+        }
+        for last in 0..n {
+            let cost = dp[mask][last];
+            if cost == INF {
+                continue;
+            }
+            for next in 0..n {
+                if mask & (1 << next) != 0 || matrix[last][next] == u32::MAX {
+                    continue;
+                }
+                let next_mask = mask | (1 << next);
+                let new_cost = cost + matrix[last][next] as u64;
+                if new_cost < dp[next_mask][next] {
+                    dp[next_mask][next] = new_cost;
+                    parent[next_mask][next] = last;
+                }
+            }
+        }
+    }
+
+    // 全員を訪問した状態の中から最良の終点を選ぶ
+    let all = full - 1;
+    let mut best = INF;
+    let mut best_last = usize::MAX;
+    for last in 0..n {
+        if dp[all][last] == INF {
+            continue;
+        }
+        let total = if round_trip {
+            if matrix[last][0] == u32::MAX {
+                continue;
+            }
+            dp[all][last] + matrix[last][0] as u64
+        } else {
+            dp[all][last]
+        };
+        if total < best {
+            best = total;
+            best_last = last;
+        }
+    }
+    if best == INF {
+        return None;
+    }
+
+    // parentをたどって訪問順を復元する
+    let mut order = Vec::with_capacity(n + 1);
+    let mut mask = all;
+    let mut current = best_last;
+    while current != usize::MAX {
+        order.push(current);
+        let prev = parent[mask][current];
+        mask &= !(1 << current);
+        current = prev;
+    }
+    order.reverse();
+    if round_trip {
+        order.push(0);
+    }
+
+    Some((order, best))
+}
+
+// 最近傍法 + 2-opt による近似解
+// 1. デポから「まだ訪問していない一番近い訪問先」へ順に進む
+//    ただし、そこから残りの訪問先全てに行けるものだけを候補にする
+//    （一方通行の先で行き止まりにならないように）
+//    round_tripなら、最後の訪問先からデポに戻れることも候補の条件にする
+//    （戻れる順番が見つからなければ、held_karp と同じく None）
+// 2. 2-opt: 経路の一部区間を反転してコストが下がる限り改善を続ける
+pub fn nearest_neighbor_two_opt(
+    matrix: &[Vec<u32>],
+    round_trip: bool,
+) -> Option<(Vec<usize>, u64)> {
+    let n = matrix.len();
+    if n == 0 {
+        return None;
+    }
+
+    // 最近傍法で初期解を作る
+    let mut visited = vec![false; n];
+    let mut tour = vec![0];
+    visited[0] = true;
+    let mut current = 0;
+    for _ in 1..n {
+        let keeps_rest_reachable =
+            |j: usize| (0..n).all(|k| visited[k] || k == j || matrix[j][k] != u32::MAX);
+        // jが最後の訪問先ならj自身が、そうでなければ残りの誰かがデポに戻れること
+        let keeps_depot_reachable = |j: usize| {
+            if !round_trip {
+                return true;
+            }
+            if tour.len() + 1 == n {
+                return matrix[j][0] != u32::MAX;
+            }
+            (0..n).any(|k| !visited[k] && k != j && matrix[k][0] != u32::MAX)
+        };
+        let next = (0..n)
+            .filter(|&j| {
+                !visited[j]
+                    && matrix[current][j] != u32::MAX
+                    && keeps_rest_reachable(j)
+                    && keeps_depot_reachable(j)
+            })
+            .min_by_key(|&j| matrix[current][j])?;
+        visited[next] = true;
+        tour.push(next);
+        current = next;
+    }
+    if round_trip {
+        if matrix[current][0] == u32::MAX {
+            return None;
+        }
+        tour.push(0);
+    }
+
+    let mut best_cost = tour_cost(matrix, &tour);
+    if best_cost == INF {
+        return None;
+    }
+
+    // 2-opt: tour[i..=j]を反転して良くなれば採用する
+    // 有向グラフでも正しく比較できるよう、毎回経路全体のコストを計算し直す
+    let last = if round_trip {
+        tour.len() - 1
+    } else {
+        tour.len()
+    };
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for i in 1..last {
+            for j in (i + 1)..last {
+                tour[i..=j].reverse();
+                let cost = tour_cost(matrix, &tour);
+                if cost < best_cost {
+                    best_cost = cost;
+                    improved = true;
+                } else {
+                    tour[i..=j].reverse();
+                }
+            }
+        }
+    }
+
+    Some((tour, best_cost))
+}
+
+// 複数拠点の配送ルートを計画する
+// depot: 出発地点、stops: 訪問先（順不同）、round_trip: デポに戻るかどうか
+// デポと訪問先を合わせて HELD_KARP_LIMIT 地点以下なら厳密解、それより多ければ近似解を返す
+// 全ての訪問先を回る順番がない（どこかに到達できない）場合はNone
+pub fn plan_route(
+    graph: &Graph,
+    depot: usize,
+    stops: &[usize],
+    round_trip: bool,
+) -> Option<RoutePlan> {
+    // デポを先頭にして、重複する訪問先を取り除く
+    let mut points = vec![depot];
+    for &stop in stops {
+        if !points.contains(&stop) {
+            points.push(stop);
+        }
+    }
+
+    let (matrix, previous_list) = distance_matrix(graph, &points);
+    let exact = points.len() <= HELD_KARP_LIMIT;
+    let (tour, cost) = if exact {
+        held_karp(&matrix, round_trip)?
+    } else {
+        nearest_neighbor_two_opt(&matrix, round_trip)?
+    };
+
+    // 区間ごとに経路を復元してつなげる（つなぎ目のノードは重複させない）
+    let mut path = vec![depot];
+    for pair in tour.windows(2) {
+        let (from, to) = (pair[0], pair[1]);
+        let segment = reconstruct_path(&previous_list[from], points[from], points[to]);
+        path.extend(segment.into_iter().skip(1));
+    }
+
+    Some(RoutePlan {
+        order: tour.iter().map(|&i| points[i]).collect(),
+        path,
+        cost,
+        exact,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn delivery_graph() -> Graph {
+        vec![
+            vec![(1, 5), (2, 2)],
+            vec![(0, 5), (2, 3), (3, 7)],
+            vec![(0, 2), (1, 3), (3, 1), (4, 4)],
+            vec![(1, 7), (2, 1), (4, 6)],
+            vec![(2, 4), (3, 6)],
+        ]
+    }
+
+    // 全順列を試す素朴な解（テスト用の参照実装）
+    fn brute_force(matrix: &[Vec<u32>], round_trip: bool) -> u64 {
+        fn permute(
+            matrix: &[Vec<u32>],
+            round_trip: bool,
+            tour: &mut Vec<usize>,
+            used: &mut Vec<bool>,
+            best: &mut u64,
+        ) {
+            if tour.len() == matrix.len() {
+                if round_trip {
+                    tour.push(0);
+                }
+                *best = (*best).min(tour_cost(matrix, tour));
+                if round_trip {
+                    tour.pop();
+                }
+                return;
+            }
+            for next in 1..matrix.len() {
+                if !used[next] {
+                    used[next] = true;
+                    tour.push(next);
+                    permute(matrix, round_trip, tour, used, best);
+                    tour.pop();
+                    used[next] = false;
+                }
+            }
+        }
+        let mut best = INF;
+        let mut used = vec![false; matrix.len()];
+        used[0] = true;
+        permute(matrix, round_trip, &mut vec![0], &mut used, &mut best);
+        best
+    }
+
+    #[test]
+    fn test_plan_route_delivery_network() {
+        let graph = delivery_graph();
+        let plan = plan_route(&graph, 0, &[1, 3, 4], true).unwrap();
+        assert!(plan.exact);
+        assert_eq!(plan.cost, 20);
+        assert_eq!(plan.order.first(), Some(&0));
+        assert_eq!(plan.order.last(), Some(&0));
+        // 展開した経路の各区間がグラフの辺になっている
+        for pair in plan.path.windows(2) {
+            assert!(graph[pair[0]].iter().any(|&(to, _)| to == pair[1]));
+        }
+    }
+
+    #[test]
+    fn test_held_karp_matches_brute_force() {
+        let matrix = vec![
+            vec![0, 29, 20, 21, 16, 31],
+            vec![29, 0, 15, 29, 28, 40],
+            vec![20, 15, 0, 15, 14, 25],
+            vec![21, 29, 15, 0, 4, 12],
+            vec![16, 28, 14, 4, 0, 16],
+            vec![31, 40, 25, 12, 16, 0],
+        ];
+        for round_trip in [true, false] {
+            let (_, cost) = held_karp(&matrix, round_trip).unwrap();
+            assert_eq!(cost, brute_force(&matrix, round_trip));
+            let (_, heuristic) = nearest_neighbor_two_opt(&matrix, round_trip).unwrap();
+            assert!(heuristic >= cost);
+        }
+    }
+
+    #[test]
+    fn test_unreachable_stop() {
+        let graph: Graph = vec![vec![(1, 1)], vec![(0, 1)], vec![]];
+        assert_eq!(plan_route(&graph, 0, &[1, 2], false), None);
+    }
+
+    #[test]
+    fn test_heuristic_avoids_dead_end() {
+        // 0 → 1 が一番近いが、1 は行き止まり（どこへも行けない）
+        // 0 → 2 → 3 → ... → 14 → 1 の順なら全員を回れる
+        let mut graph: Graph = (0..15).map(|node| vec![(node + 1, 1)]).collect();
+        graph[0] = vec![(1, 1), (2, 5)];
+        graph[1].clear();
+        graph[14] = vec![(1, 1)];
+        let stops: Vec<usize> = (1..15).collect();
+        let plan = plan_route(&graph, 0, &stops, false).unwrap();
+        assert!(!plan.exact);
+        assert_eq!(plan.cost, 5 + 12 + 1);
+        assert_eq!(plan.order.last(), Some(&1));
+    }
+
+    #[test]
+    fn test_heuristic_round_trip_returns_to_depot() {
+        // 0 → 1 → 2 と進むと 2 からデポに戻れない
+        // 0 → 2 → 1 → 0 なら戻れる
+        let inf = u32::MAX;
+        let matrix = vec![vec![0, 1, 5], vec![1, 0, 1], vec![inf, 1, 0]];
+        assert_eq!(
+            nearest_neighbor_two_opt(&matrix, true),
+            Some((vec![0, 2, 1, 0], 7))
+        );
+        assert_eq!(held_karp(&matrix, true).map(|(_, cost)| cost), Some(7));
+
+        // どの訪問先からもデポに戻れないなら、held_karp と同じく None
+        let matrix = vec![vec![0, 1, 5], vec![inf, 0, 1], vec![inf, 1, 0]];
+        assert_eq!(nearest_neighbor_two_opt(&matrix, true), None);
+        assert_eq!(held_karp(&matrix, true), None);
+    }
+}
@@ -0,0 +1,5 @@
+pub mod dfs_bfs_practice;
+pub mod dijkstra;
+pub mod rng;
+pub mod trace;
+pub mod union_find;
@@ -0,0 +1,68 @@
+// =============================================================================
+// 🎲 シード付き乱数生成器（xorshift64*）
+// =============================================================================
+//
+// 外部クレート（rand）を使わずに、テストやベンチマーク用の乱数を作ります。
+// 同じシードからは必ず同じ乱数列が出るので、失敗したテストを再現できます。
+// 暗号用途には使えません。
+
+#[derive(Debug, Clone)]
+pub struct XorShift64 {
+    state: u64,
+}
+
+impl XorShift64 {
+    // シードから生成器を作る（xorshiftは状態0だと0しか出さないので避ける）
+    pub fn new(seed: u64) -> XorShift64 {
+        // splitmix64で種をかき混ぜて、近いシードでも違う乱数列になるようにする
+        let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        XorShift64 {
+            state: if z == 0 { 0x2545_F491_4F6C_DD1D } else { z },
+        }
+    }
+
+    // 64ビットの乱数
+    pub fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.state = x;
+        x.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    // [0, 1) の実数
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    // [0, n) の整数（n > 0）
+    pub fn below(&mut self, n: usize) -> usize {
+        assert!(n > 0, "below(0) は範囲が空です");
+        // 128ビットの掛け算で偏りを小さくする（Lemireの方法の簡易版）
+        ((self.next_u64() as u128 * n as u128) >> 64) as usize
+    }
+
+    // [low, high] の整数（両端を含む）
+    pub fn range_i32(&mut self, low: i32, high: i32) -> i32 {
+        assert!(low <= high, "range_i32: low({}) > high({})", low, high);
+        let width = (high as i64 - low as i64 + 1) as u64;
+        (low as i64 + ((self.next_u64() as u128 * width as u128) >> 64) as i64) as i32
+    }
+
+    // 確率pでtrue
+    pub fn chance(&mut self, p: f64) -> bool {
+        self.next_f64() < p
+    }
+
+    // Fisher-Yatesシャッフル
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i + 1);
+            items.swap(i, j);
+        }
+    }
+}
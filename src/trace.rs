@@ -0,0 +1,249 @@
+use std::io::Write;
+
+// =============================================================================
+// 🔍 探索のトレース: ダイクストラ法やBFSの途中経過を観察する
+// =============================================================================
+//
+// 探索関数は SearchObserver を受け取り、次のタイミングで通知します。
+//   on_pop        … キュー（ヒープ）からノードを取り出した
+//   on_skip_stale … 取り出したノードが古い情報だったのでスキップした
+//   on_visit      … ノードの距離が確定した（BFSでは訪問済みにした）
+//   on_relax      … 隣のノードへの距離を更新した
+//
+// 学習用のテキスト表示（TextTrace）、機械で読むためのJSON Lines（JsonLinesTrace）、
+// 回数を数えるだけのカウンタ（TraceCounter）が、全て同じ通知を受け取ります。
+
+// トレースに出せるノード（グラフのノード番号やグリッドの座標）
+pub trait TraceNode: Copy {
+    // 人が読むための表示（例: 3, (1, 2)）
+    fn label(&self) -> String;
+    // JSONの値としての表示（例: 3, [1,2]）
+    fn to_json(&self) -> String;
+}
+
+impl TraceNode for usize {
+    fn label(&self) -> String {
+        self.to_string()
+    }
+
+    fn to_json(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl TraceNode for (usize, usize) {
+    fn label(&self) -> String {
+        format!("({}, {})", self.0, self.1)
+    }
+
+    fn to_json(&self) -> String {
+        format!("[{},{}]", self.0, self.1)
+    }
+}
+
+// 探索の通知を受け取るトレイト（必要なメソッドだけ実装すればよい）
+pub trait SearchObserver<N: TraceNode> {
+    fn on_pop(&mut self, _node: N, _cost: u64) {}
+    fn on_skip_stale(&mut self, _node: N, _cost: u64) {}
+    fn on_visit(&mut self, _node: N, _cost: u64) {}
+    fn on_relax(&mut self, _from: N, _to: N, _cost: u64) {}
+}
+
+// 何もしないオブザーバ（トレース不要のときに使う）
+pub struct NoTrace;
+
+impl<N: TraceNode> SearchObserver<N> for NoTrace {}
+
+// &mut で渡したオブザーバもそのまま使えるようにする
+impl<N: TraceNode, O: SearchObserver<N>> SearchObserver<N> for &mut O {
+    fn on_pop(&mut self, node: N, cost: u64) {
+        (**self).on_pop(node, cost)
+    }
+
+    fn on_skip_stale(&mut self, node: N, cost: u64) {
+        (**self).on_skip_stale(node, cost)
+    }
+
+    fn on_visit(&mut self, node: N, cost: u64) {
+        (**self).on_visit(node, cost)
+    }
+
+    fn on_relax(&mut self, from: N, to: N, cost: u64) {
+        (**self).on_relax(from, to, cost)
+    }
+}
+
+// 2つのオブザーバに同じ通知を送る
+// 例: (TextTrace::new(io::stdout()), TraceCounter::default())
+impl<N: TraceNode, A: SearchObserver<N>, B: SearchObserver<N>> SearchObserver<N> for (A, B) {
+    fn on_pop(&mut self, node: N, cost: u64) {
+        self.0.on_pop(node, cost);
+        self.1.on_pop(node, cost);
+    }
+
+    fn on_skip_stale(&mut self, node: N, cost: u64) {
+        self.0.on_skip_stale(node, cost);
+        self.1.on_skip_stale(node, cost);
+    }
+
+    fn on_visit(&mut self, node: N, cost: u64) {
+        self.0.on_visit(node, cost);
+        self.1.on_visit(node, cost);
+    }
+
+    fn on_relax(&mut self, from: N, to: N, cost: u64) {
+        self.0.on_relax(from, to, cost);
+        self.1.on_relax(from, to, cost);
+    }
+}
+
+// 学習用のテキスト表示
+//   現在地: (0, 0) 距離: 0
+//     → (1, 0) へ移動可能
+// on_visit は on_pop / on_relax と同じ内容になるので表示しない
+pub struct TextTrace<W: Write> {
+    out: W,
+}
+
+impl<W: Write> TextTrace<W> {
+    pub fn new(out: W) -> TextTrace<W> {
+        TextTrace { out }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+impl<N: TraceNode, W: Write> SearchObserver<N> for TextTrace<W> {
+    // トレースの書き込みに失敗しても探索自体は続けるので、エラーは無視する
+    fn on_pop(&mut self, node: N, cost: u64) {
+        let _ = writeln!(self.out, "  現在地: {} 距離: {}", node.label(), cost);
+    }
+
+    fn on_skip_stale(&mut self, node: N, cost: u64) {
+        let _ = writeln!(
+            self.out,
+            "    → {} (距離 {}) は処理済みなのでスキップ",
+            node.label(),
+            cost
+        );
+    }
+
+    fn on_relax(&mut self, _from: N, to: N, _cost: u64) {
+        let _ = writeln!(self.out, "    → {} へ移動可能", to.label());
+    }
+}
+
+// JSON Lines形式（1行に1イベント）
+// {"event":"relax","from":[0,0],"to":[1,0],"cost":1}
+pub struct JsonLinesTrace<W: Write> {
+    out: W,
+}
+
+impl<W: Write> JsonLinesTrace<W> {
+    pub fn new(out: W) -> JsonLinesTrace<W> {
+        JsonLinesTrace { out }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+
+    fn node_event<N: TraceNode>(&mut self, event: &str, node: N, cost: u64) {
+        let _ = writeln!(
+            self.out,
+            "{{\"event\":\"{}\",\"node\":{},\"cost\":{}}}",
+            event,
+            node.to_json(),
+            cost
+        );
+    }
+}
+
+impl<N: TraceNode, W: Write> SearchObserver<N> for JsonLinesTrace<W> {
+    fn on_pop(&mut self, node: N, cost: u64) {
+        self.node_event("pop", node, cost);
+    }
+
+    fn on_skip_stale(&mut self, node: N, cost: u64) {
+        self.node_event("skip_stale", node, cost);
+    }
+
+    fn on_visit(&mut self, node: N, cost: u64) {
+        self.node_event("visit", node, cost);
+    }
+
+    fn on_relax(&mut self, from: N, to: N, cost: u64) {
+        let _ = writeln!(
+            self.out,
+            "{{\"event\":\"relax\",\"from\":{},\"to\":{},\"cost\":{}}}",
+            from.to_json(),
+            to.to_json(),
+            cost
+        );
+    }
+}
+
+// 各イベントの回数を数える（探索の効率を比べるときに使う）
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TraceCounter {
+    pub pops: usize,
+    pub stale_skips: usize,
+    pub visits: usize,
+    pub relaxations: usize,
+}
+
+impl<N: TraceNode> SearchObserver<N> for TraceCounter {
+    fn on_pop(&mut self, _node: N, _cost: u64) {
+        self.pops += 1;
+    }
+
+    fn on_skip_stale(&mut self, _node: N, _cost: u64) {
+        self.stale_skips += 1;
+    }
+
+    fn on_visit(&mut self, _node: N, _cost: u64) {
+        self.visits += 1;
+    }
+
+    fn on_relax(&mut self, _from: N, _to: N, _cost: u64) {
+        self.relaxations += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dijkstra::{dijkstra, dijkstra_traced, Graph};
+
+    #[test]
+    fn test_dijkstra_trace_counts() {
+        let graph: Graph = vec![
+            vec![(1, 5), (2, 2)],
+            vec![(0, 5), (2, 3), (3, 7)],
+            vec![(0, 2), (1, 3), (3, 1), (4, 4)],
+            vec![(1, 7), (2, 1), (4, 6)],
+            vec![(2, 4), (3, 6)],
+        ];
+        let mut counter = TraceCounter::default();
+        let distances = dijkstra_traced(&graph, 0, &mut counter);
+        assert_eq!(distances, dijkstra(&graph, 0));
+        // 全ノードが1回ずつ確定し、残りの取り出しは古い情報のスキップ
+        assert_eq!(counter.visits, 5);
+        assert_eq!(counter.pops, counter.visits + counter.stale_skips);
+        assert_eq!(counter.pops, counter.relaxations + 1);
+    }
+
+    #[test]
+    fn test_text_trace_format() {
+        let graph: Graph = vec![vec![(1, 4)], vec![]];
+        let mut trace = TextTrace::new(Vec::new());
+        dijkstra_traced(&graph, 0, &mut trace);
+        let output = String::from_utf8(trace.into_inner()).unwrap();
+        assert_eq!(
+            output,
+            "  現在地: 0 距離: 0\n    → 1 へ移動可能\n  現在地: 1 距離: 4\n"
+        );
+    }
+}
@@ -0,0 +1,196 @@
+// =============================================================================
+// 🔗 Union-Find（素集合データ構造）
+// =============================================================================
+//
+// 「aとbは同じグループか？」「aのグループとbのグループをまとめる」を
+// ほぼ定数時間で行うデータ構造です。
+//
+// - UnionFind         … 経路圧縮 + サイズによる併合（普段使う版）
+// - RollbackUnionFind … 経路圧縮をしない代わりに、直前の併合を取り消せる版
+//                       （オフラインで削除を扱うときに使う）
+
+#[derive(Debug, Clone)]
+pub struct UnionFind {
+    // parent[x] = xの親（根なら自分自身）
+    parent: Vec<usize>,
+    // size[root] = 根rootのグループの要素数
+    size: Vec<usize>,
+    // グループの数
+    groups: usize,
+}
+
+impl UnionFind {
+    pub fn new(n: usize) -> UnionFind {
+        UnionFind {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            groups: n,
+        }
+    }
+
+    // 要素数
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    // 新しい要素を1つ追加して、その番号を返す
+    pub fn push(&mut self) -> usize {
+        let id = self.parent.len();
+        self.parent.push(id);
+        self.size.push(1);
+        self.groups += 1;
+        id
+    }
+
+    // xの根を求める（経路圧縮: たどったノードを根に直接つなぎ直す）
+    // 再帰すると長い鎖でスタックを使い切るので、ループで2回たどる
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut current = x;
+        while self.parent[current] != root {
+            let next = self.parent[current];
+            self.parent[current] = root;
+            current = next;
+        }
+        root
+    }
+
+    // aとbのグループをまとめる（既に同じならfalse）
+    // 小さいグループを大きいグループの下につなぐ
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut ra, mut rb) = (self.find(a), self.find(b));
+        if ra == rb {
+            return false;
+        }
+        if self.size[ra] < self.size[rb] {
+            std::mem::swap(&mut ra, &mut rb);
+        }
+        self.parent[rb] = ra;
+        self.size[ra] += self.size[rb];
+        self.groups -= 1;
+        true
+    }
+
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    // xが属するグループの要素数
+    pub fn size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+
+    // グループの数
+    pub fn groups(&self) -> usize {
+        self.groups
+    }
+}
+
+// 取り消し可能なUnion-Find
+// 経路圧縮をすると取り消しが難しくなるので、サイズによる併合だけで O(log n) に抑える
+#[derive(Debug, Clone)]
+pub struct RollbackUnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    // 併合の履歴: Some((下につないだ根, 上になった根)) / 何もしなかった場合はNone
+    history: Vec<Option<(usize, usize)>>,
+}
+
+impl RollbackUnionFind {
+    pub fn new(n: usize) -> RollbackUnionFind {
+        RollbackUnionFind {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            history: Vec::new(),
+        }
+    }
+
+    pub fn find(&self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            x = self.parent[x];
+        }
+        x
+    }
+
+    pub fn size(&self, x: usize) -> usize {
+        self.size[self.find(x)]
+    }
+
+    // aとbのグループをまとめる
+    // 併合した場合は (併合前のaのサイズ, 併合前のbのサイズ) を返す
+    pub fn union(&mut self, a: usize, b: usize) -> Option<(usize, usize)> {
+        let (ra, rb) = (self.find(a), self.find(b));
+        if ra == rb {
+            self.history.push(None);
+            return None;
+        }
+        let sizes = (self.size[ra], self.size[rb]);
+        let (big, small) = if self.size[ra] >= self.size[rb] {
+            (ra, rb)
+        } else {
+            (rb, ra)
+        };
+        self.parent[small] = big;
+        self.size[big] += self.size[small];
+        self.history.push(Some((small, big)));
+        Some(sizes)
+    }
+
+    // 履歴の長さ（rollbackで戻る位置の目印）
+    pub fn snapshot(&self) -> usize {
+        self.history.len()
+    }
+
+    // snapshotの時点まで併合を取り消す
+    pub fn rollback(&mut self, snapshot: usize) {
+        while self.history.len() > snapshot {
+            if let Some((small, big)) = self.history.pop().flatten() {
+                self.parent[small] = small;
+                self.size[big] -= self.size[small];
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_union_find() {
+        let mut uf = UnionFind::new(5);
+        assert!(uf.union(0, 1));
+        assert!(uf.union(3, 4));
+        assert!(!uf.union(1, 0));
+        assert!(uf.same(0, 1));
+        assert!(!uf.same(1, 3));
+        assert_eq!(uf.size(4), 2);
+        assert_eq!(uf.groups(), 3);
+        let id = uf.push();
+        assert!(uf.union(id, 0));
+        assert_eq!((uf.len(), uf.size(1), uf.groups()), (6, 3, 3));
+    }
+
+    #[test]
+    fn test_rollback() {
+        let mut uf = RollbackUnionFind::new(4);
+        assert_eq!(uf.union(0, 1), Some((1, 1)));
+        let mark = uf.snapshot();
+        assert_eq!(uf.union(2, 3), Some((1, 1)));
+        assert_eq!(uf.union(0, 3), Some((2, 2)));
+        assert_eq!(uf.union(1, 2), None);
+        assert_eq!(uf.size(0), 4);
+        uf.rollback(mark);
+        assert_eq!(uf.size(0), 2);
+        assert_eq!(uf.size(3), 1);
+        assert_ne!(uf.find(0), uf.find(2));
+    }
+}